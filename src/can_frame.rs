@@ -0,0 +1,102 @@
+/// The byte order a signal is packed into a CAN frame with
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ByteOrder {
+    /// Intel byte order, where the start bit is the least significant bit
+    /// and the signal grows towards higher bit numbers
+    LittleEndian,
+    /// Motorola byte order, where the start bit is the most significant bit
+    /// and the signal continues through the lower bits of the byte, then on
+    /// to the most significant bit of the next byte (sawtooth numbering)
+    BigEndian,
+}
+
+/// The frame bit numbers a `length` bit signal starting at `start_bit` occupies, from its least
+/// significant bit for Intel and from its most significant bit for Motorola
+///
+/// Bits are numbered as in a DBC file, `byte * 8 + bit` where bit 0 is the least
+/// significant bit of the byte.
+pub fn bit_positions(start_bit: u16, length: u8, byte_order: ByteOrder) -> Vec<usize> {
+    let mut position = start_bit as usize;
+    let mut positions = Vec::with_capacity(length as usize);
+
+    for _ in 0..length {
+        positions.push(position);
+        position = match byte_order {
+            ByteOrder::LittleEndian => position + 1,
+            // Past bit 0 of a byte, continue from bit 7 of the next one
+            ByteOrder::BigEndian if position.is_multiple_of(8) => position + 15,
+            ByteOrder::BigEndian => position - 1,
+        };
+    }
+    positions
+}
+
+/// Pack the lowest `length` bits of `value` into `frame` starting at `start_bit`
+///
+/// Bits are numbered as in `bit_positions`. Bits that would fall outside the frame are dropped.
+pub fn pack_bits(frame: &mut [u8], value: u64, start_bit: u16, length: u8, byte_order: ByteOrder) {
+    for (i, position) in bit_positions(start_bit, length, byte_order)
+        .into_iter()
+        .enumerate()
+    {
+        // Intel walks up from the least significant bit, Motorola down from the most
+        let bit = match byte_order {
+            ByteOrder::LittleEndian => i,
+            ByteOrder::BigEndian => length as usize - 1 - i,
+        };
+
+        if let Some(byte) = frame.get_mut(position / 8) {
+            let mask = 1 << (position % 8);
+            // Bits beyond the width of the value are zero
+            if value.checked_shr(bit as u32).unwrap_or(0) & 1 == 1 {
+                *byte |= mask;
+            } else {
+                *byte &= !mask;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod can_frame_tests {
+    use super::*;
+
+    #[test]
+    fn little_endian_test() {
+        let mut frame = [0u8; 8];
+        pack_bits(&mut frame, 0xAB, 0, 8, ByteOrder::LittleEndian);
+        assert_eq!(frame, [0xAB, 0, 0, 0, 0, 0, 0, 0]);
+
+        // 12 bits straddling bytes 0 and 1
+        let mut frame = [0u8; 8];
+        pack_bits(&mut frame, 0x123, 4, 12, ByteOrder::LittleEndian);
+        assert_eq!(frame, [0x30, 0x12, 0, 0, 0, 0, 0, 0]);
+
+        // 16 bits straddling bytes 2, 3 and 4
+        let mut frame = [0u8; 8];
+        pack_bits(&mut frame, 0xBEEF, 20, 16, ByteOrder::LittleEndian);
+        assert_eq!(frame, [0, 0, 0xF0, 0xEE, 0x0B, 0, 0, 0]);
+    }
+
+    #[test]
+    fn big_endian_test() {
+        let mut frame = [0u8; 8];
+        pack_bits(&mut frame, 0xAB, 7, 8, ByteOrder::BigEndian);
+        assert_eq!(frame, [0xAB, 0, 0, 0, 0, 0, 0, 0]);
+
+        // 12 bits with the most significant bit at bit 7
+        let mut frame = [0u8; 8];
+        pack_bits(&mut frame, 0x123, 7, 12, ByteOrder::BigEndian);
+        assert_eq!(frame, [0x12, 0x30, 0, 0, 0, 0, 0, 0]);
+
+        // 12 bits with the most significant bit at bit 3, which naive byte reversal gets wrong
+        let mut frame = [0u8; 8];
+        pack_bits(&mut frame, 0x5A5, 3, 12, ByteOrder::BigEndian);
+        assert_eq!(frame, [0x05, 0xA5, 0, 0, 0, 0, 0, 0]);
+
+        // 16 bits with the most significant bit at bit 19 spanning bytes 2, 3 and 4
+        let mut frame = [0u8; 8];
+        pack_bits(&mut frame, 0xBEEF, 19, 16, ByteOrder::BigEndian);
+        assert_eq!(frame, [0, 0, 0x0B, 0xEE, 0xF0, 0, 0, 0]);
+    }
+}
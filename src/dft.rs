@@ -0,0 +1,146 @@
+use std::f64::consts::PI;
+
+/// A complex number as (real, imaginary)
+pub type Complex = (f64, f64);
+
+fn mul((a_re, a_im): Complex, (b_re, b_im): Complex) -> Complex {
+    (a_re * b_re - a_im * b_im, a_re * b_im + a_im * b_re)
+}
+
+/// The discrete Fourier transform of `samples`, or its inverse (without the 1/n scaling)
+///
+/// A radix-2 FFT for power-of-two lengths, and Bluestein's algorithm on top of it for the rest,
+/// so always O(n log n)
+pub fn dft(samples: &[Complex], inverse: bool) -> Vec<Complex> {
+    let n = samples.len();
+    if n == 0 || n.is_power_of_two() {
+        let mut output = samples.to_vec();
+        fft_in_place(&mut output, inverse);
+        output
+    } else {
+        bluestein(samples, inverse)
+    }
+}
+
+/// The power `|X_k|²` in each bin of the DFT of the real `samples`, from DC up to the Nyquist bin
+#[cfg(test)]
+pub fn power_spectrum(samples: &[f64]) -> Vec<f64> {
+    let complex: Vec<Complex> = samples.iter().map(|&x| (x, 0.0)).collect();
+    dft(&complex, false)
+        .iter()
+        .take(samples.len() / 2 + 1)
+        .map(|&(re, im)| re * re + im * im)
+        .collect()
+}
+
+/// An iterative radix-2 FFT of a power-of-two length buffer
+fn fft_in_place(buffer: &mut [Complex], inverse: bool) {
+    let n = buffer.len();
+    if n <= 1 {
+        return;
+    }
+
+    // Put the samples in bit-reversed order, so each pass combines neighbouring blocks
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            buffer.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let (sin, cos) = (sign * 2.0 * PI / len as f64).sin_cos();
+        for block in buffer.chunks_mut(len) {
+            let (low, high) = block.split_at_mut(len / 2);
+            let mut twiddle = (1.0, 0.0);
+            for (a, b) in low.iter_mut().zip(high.iter_mut()) {
+                let t = mul(twiddle, *b);
+                *b = (a.0 - t.0, a.1 - t.1);
+                *a = (a.0 + t.0, a.1 + t.1);
+                twiddle = mul(twiddle, (cos, sin));
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Bluestein's algorithm, which turns a DFT of any length into a convolution that is done with
+/// power-of-two FFTs
+fn bluestein(samples: &[Complex], inverse: bool) -> Vec<Complex> {
+    let n = samples.len();
+    let m = (2 * n - 1).next_power_of_two();
+    let sign = if inverse { 1.0 } else { -1.0 };
+
+    // exp(sign * i * pi * k^2 / n), with k^2 reduced mod 2n so the angle stays accurate
+    let chirp: Vec<Complex> = (0..n)
+        .map(|k| {
+            let k2 = (k as u128 * k as u128 % (2 * n as u128)) as f64;
+            let (sin, cos) = (sign * PI * k2 / n as f64).sin_cos();
+            (cos, sin)
+        })
+        .collect();
+
+    let mut a = vec![(0.0, 0.0); m];
+    for ((slot, &sample), &w) in a.iter_mut().zip(samples).zip(&chirp) {
+        *slot = mul(sample, w);
+    }
+    let mut b = vec![(0.0, 0.0); m];
+    for (k, &(re, im)) in chirp.iter().enumerate() {
+        b[k] = (re, -im);
+        if k > 0 {
+            b[m - k] = (re, -im);
+        }
+    }
+
+    fft_in_place(&mut a, false);
+    fft_in_place(&mut b, false);
+    for (x, &y) in a.iter_mut().zip(&b) {
+        *x = mul(*x, y);
+    }
+    fft_in_place(&mut a, true);
+
+    a.iter()
+        .zip(&chirp)
+        .map(|(&(re, im), &w)| mul((re / m as f64, im / m as f64), w))
+        .collect()
+}
+
+#[cfg(test)]
+mod dft_tests {
+    use super::*;
+
+    #[test]
+    fn dft_test() {
+        // Compare against the transform written out directly
+        let naive = |samples: &[Complex], inverse: bool| -> Vec<Complex> {
+            let n = samples.len();
+            let sign = if inverse { 1.0 } else { -1.0 };
+            (0..n)
+                .map(|k| {
+                    samples.iter().enumerate().fold((0.0, 0.0), |sum, (j, &x)| {
+                        let angle = sign * 2.0 * PI * ((k * j) % n) as f64 / n as f64;
+                        let product = mul(x, (angle.cos(), angle.sin()));
+                        (sum.0 + product.0, sum.1 + product.1)
+                    })
+                })
+                .collect()
+        };
+
+        for n in (0..=20).chain([64, 100, 127]) {
+            let samples: Vec<Complex> = (0..n)
+                .map(|i| ((i as f64 * 0.7).sin() * 100.0, (i as f64 * 1.3).cos()))
+                .collect();
+            for inverse in [false, true] {
+                let fast = dft(&samples, inverse);
+                let slow = naive(&samples, inverse);
+                assert_eq!(fast.len(), n);
+                for (f, s) in fast.iter().zip(&slow) {
+                    assert!((f.0 - s.0).abs() < 1e-8 && (f.1 - s.1).abs() < 1e-8, "{n}");
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,21 @@
-mod signal_generator;
-mod signal_type;
+// pyo3 0.19's `#[pymethods]` expands to impls that newer compilers flag
+#![allow(non_local_definitions)]
+
+pub mod can_frame;
+#[cfg(any(test, feature = "resample", feature = "spectral"))]
+mod dft;
+#[cfg(feature = "mf4")]
+pub mod mf4;
+pub mod moving_average;
+pub mod presets;
+#[cfg(feature = "resample")]
+pub mod resample;
+pub mod schedule;
+pub mod signal_generator;
+pub mod signal_type;
+pub mod signal_wrapper;
+#[cfg(feature = "stability")]
+pub mod stability;
 
 use pyo3::prelude::*;
 
@@ -9,5 +25,10 @@ fn can_message_data_generator(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<signal_generator::SignalGenerator>()?;
     m.add_function(wrap_pyfunction!(signal_generator::get_max_limit, m)?)?;
     m.add_function(wrap_pyfunction!(signal_generator::get_min_limit, m)?)?;
+    m.add_function(wrap_pyfunction!(signal_generator::bits_for_resolution, m)?)?;
+    m.add_function(wrap_pyfunction!(signal_generator::scale_for_lsb, m)?)?;
+    m.add_function(wrap_pyfunction!(signal_generator::tune_period, m)?)?;
+    m.add_function(wrap_pyfunction!(signal_generator::set_deterministic, m)?)?;
+    m.add_function(wrap_pyfunction!(signal_generator::set_global_seed, m)?)?;
     Ok(())
 }
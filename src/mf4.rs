@@ -0,0 +1,239 @@
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::signal_type::generators::{sample_times, Signal};
+
+/// An MDF 4.10 file being built in memory, one block after another
+struct Mdf {
+    bytes: Vec<u8>,
+}
+
+impl Mdf {
+    /// Append a block with the given links and data, padded to 8 bytes, returning its offset
+    fn block(&mut self, id: &[u8; 4], links: &[u64], data: &[u8]) -> u64 {
+        let offset = self.bytes.len() as u64;
+        let padded = data.len().next_multiple_of(8);
+        let length = 24 + 8 * links.len() + padded;
+
+        self.bytes.extend_from_slice(id);
+        self.bytes.extend_from_slice(&0u32.to_le_bytes());
+        self.bytes.extend_from_slice(&(length as u64).to_le_bytes());
+        self.bytes
+            .extend_from_slice(&(links.len() as u64).to_le_bytes());
+        for link in links {
+            self.bytes.extend_from_slice(&link.to_le_bytes());
+        }
+        self.bytes.extend_from_slice(data);
+        self.bytes.resize(offset as usize + length, 0);
+        offset
+    }
+
+    /// Point link `index` of the block at `block` to `target`
+    fn link(&mut self, block: u64, index: usize, target: u64) {
+        let position = block as usize + 24 + 8 * index;
+        self.bytes[position..position + 8].copy_from_slice(&target.to_le_bytes());
+    }
+
+    /// Append a text block, returning its offset
+    fn text(&mut self, text: &str) -> u64 {
+        let mut data = text.as_bytes().to_vec();
+        data.push(0);
+        self.block(b"##TX", &[], &data)
+    }
+
+    /// Append an XML metadata block, returning its offset
+    fn metadata(&mut self, xml: &str) -> u64 {
+        let mut data = xml.as_bytes().to_vec();
+        data.push(0);
+        self.block(b"##MD", &[], &data)
+    }
+
+    /// Append a channel block for an 8 byte value at `byte_offset`, returning its offset
+    fn channel(&mut self, name: &str, master: bool, data_type: u8, byte_offset: u32) -> u64 {
+        let name = self.text(name);
+
+        let mut data = Vec::with_capacity(72);
+        data.push(if master { 2 } else { 0 }); // Channel type, 2 is the master
+        data.push(if master { 1 } else { 0 }); // Sync type, 1 is time
+        data.push(data_type);
+        data.push(0); // Bit offset
+        data.extend_from_slice(&byte_offset.to_le_bytes());
+        data.extend_from_slice(&64u32.to_le_bytes()); // Bit count
+        data.extend_from_slice(&0u32.to_le_bytes()); // Flags
+        data.extend_from_slice(&0u32.to_le_bytes()); // Invalidation bit
+        data.extend_from_slice(&[0, 0, 0, 0]); // Precision, reserved, attachment count
+        data.extend_from_slice(&[0; 48]); // Value, limit and extended limit ranges
+
+        // Next, composition, name, source, conversion, data, unit, comment
+        self.block(b"##CN", &[0, 0, name, 0, 0, 0, 0, 0], &data)
+    }
+}
+
+/// Write named signals to an ASAM MDF 4.10 (.mf4) file, calculated every `step` seconds from
+/// `start` up to (but not including) `end`
+///
+/// The file has one data group with a single channel group: a time channel in seconds as the
+/// master, then a signed 64-bit channel per signal with the values from `calculate`
+pub fn write_mf4(
+    path: &Path,
+    signals: &[(&str, &dyn Signal)],
+    start: f64,
+    end: f64,
+    step: f64,
+) -> io::Result<()> {
+    let mut mdf = Mdf { bytes: Vec::new() };
+
+    // Identification block
+    mdf.bytes.extend_from_slice(b"MDF     4.10    can-gen ");
+    mdf.bytes.extend_from_slice(&[0; 4]);
+    mdf.bytes.extend_from_slice(&410u16.to_le_bytes());
+    mdf.bytes.extend_from_slice(&[0; 34]);
+
+    // Header, with its first data group linked once it is written
+    let header = mdf.block(b"##HD", &[0; 6], &[0; 32]);
+
+    // File history, with the one entry readers expect saying which tool wrote the file
+    let history_comment = mdf.metadata(&format!(
+        "<FHcomment><TX>Created</TX><tool_id>{name}</tool_id>\
+         <tool_vendor>{name}</tool_vendor><tool_version>{version}</tool_version></FHcomment>",
+        name = env!("CARGO_PKG_NAME"),
+        version = env!("CARGO_PKG_VERSION"),
+    ));
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+    let mut history = Vec::with_capacity(16);
+    history.extend_from_slice(&now.to_le_bytes()); // Time in UTC nanoseconds
+    history.extend_from_slice(&[0; 8]); // Time zone and DST offsets, flags, reserved
+
+    // Next, comment
+    let file_history = mdf.block(b"##FH", &[0, history_comment], &history);
+    mdf.link(header, 1, file_history);
+
+    // Channels, linked into a chain
+    let time = mdf.channel("time", true, 4, 0);
+    let mut last = time;
+    for (i, (name, _)) in signals.iter().enumerate() {
+        let channel = mdf.channel(name, false, 2, 8 * (i as u32 + 1));
+        mdf.link(last, 0, channel);
+        last = channel;
+    }
+
+    // Records of the time followed by each value
+    let times: Vec<f64> = sample_times(start, end, step).collect();
+    let record_bytes = 8 * (signals.len() + 1);
+    let mut records = Vec::with_capacity(times.len() * record_bytes);
+    for &time in &times {
+        records.extend_from_slice(&time.to_le_bytes());
+        for (_, signal) in signals {
+            records.extend_from_slice(&signal.calculate(time).to_le_bytes());
+        }
+    }
+
+    let mut group = Vec::with_capacity(32);
+    group.extend_from_slice(&0u64.to_le_bytes()); // Record ID
+    group.extend_from_slice(&(times.len() as u64).to_le_bytes()); // Cycle count
+    group.extend_from_slice(&[0; 8]); // Flags, path separator, reserved
+    group.extend_from_slice(&(record_bytes as u32).to_le_bytes());
+    group.extend_from_slice(&0u32.to_le_bytes()); // Invalidation bytes
+
+    // Next, first channel, acquisition name, source, sample reduction, comment
+    let channel_group = mdf.block(b"##CG", &[0, time, 0, 0, 0, 0], &group);
+
+    let data = mdf.block(b"##DT", &[], &records);
+    // Next, first channel group, data, comment, then the record ID size
+    let data_group = mdf.block(b"##DG", &[0, channel_group, data, 0], &[0; 8]);
+    mdf.link(header, 0, data_group);
+
+    std::fs::write(path, mdf.bytes)
+}
+
+#[cfg(test)]
+mod mf4_tests {
+    use super::*;
+    use crate::signal_type::generators;
+
+    /// Read the little-endian u64 at `offset`
+    fn read_u64(bytes: &[u8], offset: u64) -> u64 {
+        let offset = offset as usize;
+        u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+    }
+
+    /// The block ID and links of the block at `offset`
+    fn read_block(bytes: &[u8], offset: u64) -> (&[u8], Vec<u64>) {
+        let id = &bytes[offset as usize..offset as usize + 4];
+        let link_count = read_u64(bytes, offset + 16);
+        let links = (0..link_count)
+            .map(|i| read_u64(bytes, offset + 24 + 8 * i))
+            .collect();
+        (id, links)
+    }
+
+    #[test]
+    fn write_mf4_test() {
+        let sine = generators::test_sine(1000.0, 1.0);
+        let constant = generators::test_constant(0.0);
+
+        let path = std::env::temp_dir().join("write_mf4_test.mf4");
+        write_mf4(
+            &path,
+            &[("sine", &sine), ("constant", &constant)],
+            0.0,
+            1.0,
+            0.01,
+        )
+        .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(&bytes[0..8], b"MDF     ");
+        assert_eq!(u16::from_le_bytes([bytes[28], bytes[29]]), 410);
+
+        // Header -> data group -> channel group -> channels
+        let (id, header) = read_block(&bytes, 64);
+        assert_eq!(id, b"##HD");
+
+        // Header -> file history -> its comment
+        let (id, file_history) = read_block(&bytes, header[1]);
+        assert_eq!(id, b"##FH");
+        let (id, _) = read_block(&bytes, file_history[1]);
+        assert_eq!(id, b"##MD");
+        let comment = &bytes[file_history[1] as usize + 24..];
+        let comment = &comment[..comment.iter().position(|&b| b == 0).unwrap()];
+        let comment = String::from_utf8(comment.to_vec()).unwrap();
+        assert!(comment.starts_with("<FHcomment>"), "{comment}");
+        assert!(comment.contains("<tool_id>can-message-data-generator</tool_id>"));
+
+        let (id, data_group) = read_block(&bytes, header[0]);
+        assert_eq!(id, b"##DG");
+        let (id, channel_group) = read_block(&bytes, data_group[1]);
+        assert_eq!(id, b"##CG");
+        assert_eq!(read_u64(&bytes, data_group[1] + 24 + 8 * 6 + 8), 100);
+
+        let mut names = Vec::new();
+        let mut next = channel_group[1];
+        while next != 0 {
+            let (id, links) = read_block(&bytes, next);
+            assert_eq!(id, b"##CN");
+            let name_start = links[2] as usize + 24;
+            let name_end = name_start + bytes[name_start..].iter().position(|&b| b == 0).unwrap();
+            names.push(String::from_utf8(bytes[name_start..name_end].to_vec()).unwrap());
+            next = links[0];
+        }
+        assert_eq!(names, vec!["time", "sine", "constant"]);
+
+        // The second record is at 0.01 s
+        let (id, _) = read_block(&bytes, data_group[2]);
+        assert_eq!(id, b"##DT");
+        let second = data_group[2] + 24 + 24;
+        assert_eq!(
+            f64::from_le_bytes(
+                bytes[second as usize..second as usize + 8]
+                    .try_into()
+                    .unwrap()
+            ),
+            0.01
+        );
+    }
+}
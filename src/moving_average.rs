@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+/// An iterator adaptor that averages each sample with the ones before it, emulating a
+/// smoothing filter in the acquisition chain
+///
+/// Until `window` samples have been seen, the average is over all the samples so far
+#[derive(Debug)]
+pub struct MovingAverage<I> {
+    inner: I,
+    window: usize,
+    recent: VecDeque<i64>,
+    sum: i128,
+}
+
+impl<I: Iterator<Item = i64>> MovingAverage<I> {
+    /// Average over the last `window` samples, where a window of 0 is treated as 1
+    pub fn new(inner: I, window: usize) -> Self {
+        MovingAverage {
+            inner,
+            window: window.max(1),
+            recent: VecDeque::with_capacity(window.max(1)),
+            sum: 0,
+        }
+    }
+
+    /// The delay the average introduces when the samples are `1 / sample_rate` seconds apart,
+    /// which is half the span of the window
+    pub fn group_delay(&self, sample_rate: f64) -> f64 {
+        (self.window - 1) as f64 / (2.0 * sample_rate)
+    }
+}
+
+impl<I: Iterator<Item = i64>> Iterator for MovingAverage<I> {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        let sample = self.inner.next()?;
+
+        if self.recent.len() == self.window {
+            self.sum -= self.recent.pop_front().unwrap_or_default() as i128;
+        }
+        self.recent.push_back(sample);
+        self.sum += sample as i128;
+
+        let average = self.sum as f64 / self.recent.len() as f64;
+        Some(average.round() as i64)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod moving_average_tests {
+    use super::*;
+    use crate::signal_type::generators::{self, sample_times, Signal};
+
+    fn variance(samples: &[i64]) -> f64 {
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<i64>() as f64 / count;
+        samples
+            .iter()
+            .map(|&x| (x as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count
+    }
+
+    #[test]
+    fn moving_average_test() {
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let constant = generators::test_constant(10000.0);
+
+        let raw: Vec<i64> = sample_times(0.0, 100.0, 0.01)
+            .map(|time| constant.calculate(time))
+            .collect();
+        let averaged: Vec<i64> = MovingAverage::new(raw.iter().copied(), 10)
+            .skip(10)
+            .collect();
+
+        // Averaging 10 independent samples divides the variance by 10
+        let ratio = variance(&raw[10..]) / variance(&averaged);
+        assert!((7.0..13.0).contains(&ratio), "{ratio}");
+
+        // The first outputs average over what has been seen so far
+        let ramp: Vec<i64> = MovingAverage::new([0, 2, 4, 6, 8].into_iter(), 2).collect();
+        assert_eq!(ramp, vec![0, 1, 3, 5, 7]);
+    }
+
+    #[test]
+    fn group_delay_test() {
+        let average = MovingAverage::new(std::iter::empty(), 9);
+        assert_eq!(average.group_delay(100.0), 0.04);
+
+        // A moving average of a ramp lags it by the group delay
+        let ramp = (0..100).map(|i| i * 10);
+        let lagged: Vec<i64> = MovingAverage::new(ramp, 9).skip(8).collect();
+        assert!(lagged
+            .iter()
+            .enumerate()
+            .all(|(i, &x)| x == (i as i64 + 4) * 10));
+    }
+}
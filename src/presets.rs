@@ -0,0 +1,234 @@
+use crate::signal_type::generators::{
+    hash_unit, max_count_for, Callback, Constant, Signal, Sine, Triangle,
+};
+use crate::signal_wrapper::{Dropouts, Seeded, Spikes};
+
+/// The pack voltage: a steady 100 V from an 80 V to 134.4 V pack, in 10 mV steps
+pub fn battery_voltage() -> Constant {
+    Constant {
+        minimum: 80.0,
+        maximum: 134.4,
+        amplitude: 100.0,
+        period: 0.0,
+        phase: 0.0,
+        num_bits: 16,
+        is_signed: false,
+        scale: 0.01,
+        offset: 0.0,
+    }
+}
+
+/// The motor speed: accelerating to 1200 RPM and braking back through reverse every 30 s,
+/// within ±1500 RPM in 1 RPM steps
+pub fn motor_rpm() -> Triangle {
+    Triangle {
+        minimum: -1500.0,
+        maximum: 1500.0,
+        amplitude: 1200.0,
+        period: 30.0,
+        phase: 0.0,
+        num_bits: 16,
+        is_signed: true,
+        scale: 1.0,
+        offset: 0.0,
+    }
+}
+
+/// The pack temperature: a steady 35 °C, within -20 °C to 80 °C in 0.1 °C steps
+pub fn pack_temperature() -> Constant {
+    Constant {
+        minimum: -20.0,
+        maximum: 80.0,
+        amplitude: 35.0,
+        period: 0.0,
+        phase: 0.0,
+        num_bits: 16,
+        is_signed: true,
+        scale: 0.1,
+        offset: 0.0,
+    }
+}
+
+/// The motor current: ±50 A swinging with the drive cycle every 10 s, within ±100 A in 0.1 A steps
+pub fn motor_current() -> Sine {
+    Sine {
+        minimum: -100.0,
+        maximum: 100.0,
+        amplitude: 50.0,
+        period: 10.0,
+        phase: 0.0,
+        num_bits: 16,
+        is_signed: true,
+        scale: 0.1,
+        offset: 0.0,
+    }
+}
+
+/// A calibration constant whose raw count is all ones in its `num_bits` bit field, at any time
+///
+/// The engineering value is 0, with the count coming from the offset, so there is no noise.
+/// Unsigned, so `num_bits` is limited to 1 to 63.
+pub fn all_ones(num_bits: u8) -> Constant {
+    Constant {
+        offset: -(max_count_for(num_bits, false) as f64),
+        ..all_zeros(num_bits)
+    }
+}
+
+/// A calibration constant whose raw count is all zeros in its `num_bits` bit field, at any time
+pub fn all_zeros(num_bits: u8) -> Constant {
+    Constant {
+        minimum: 0.0,
+        maximum: 0.0,
+        amplitude: 0.0,
+        period: 0.0,
+        phase: 0.0,
+        num_bits,
+        is_signed: false,
+        scale: 1.0,
+        offset: 0.0,
+    }
+}
+
+/// A faulty channel for fault-injection tests: `base`, with its usual noise, plus spikes on a
+/// `spike_rate` fraction of samples and 100 ms dropouts in a `dropout_rate` fraction of the time
+///
+/// The noise, spikes and dropouts are all fixed by `seed`, so the same times always give the
+/// same values
+pub fn glitchy(
+    base: Box<dyn Signal>,
+    spike_rate: f64,
+    dropout_rate: f64,
+    seed: u64,
+) -> Box<dyn Signal> {
+    // Other seeds again, so neither the noise nor the dropouts line up with the spikes
+    let seeded = Seeded::new(base, seed.wrapping_add(1));
+    let spiky = Spikes::new(Box::new(seeded), spike_rate, seed);
+    Box::new(Dropouts::new(Box::new(spiky), dropout_rate, 0.1, !seed))
+}
+
+/// Smooth noise from -1 to 1 that wanders between random values at knots `spacing` seconds apart
+fn value_noise(seed: u64, time: f64, spacing: f64) -> f64 {
+    let position = time / spacing;
+    let knot = position.floor();
+    let t = position - knot;
+    let eased = t * t * (3.0 - 2.0 * t);
+
+    let from = hash_unit(seed, knot);
+    let to = hash_unit(seed, knot + 1.0);
+    2.0 * (from + (to - from) * eased) - 1.0
+}
+
+/// An organic-looking trace within `base_range` (minimum, maximum) that is reproducible from
+/// `seed` alone: a slow wander over tens of seconds, faster correlated noise and rare spikes
+///
+/// Nothing is drawn from the noise RNG, so the same seed always gives the same trace
+pub fn realistic(base_range: (f64, f64), seed: u64) -> Box<dyn Signal> {
+    let (minimum, maximum) = base_range;
+    let middle = (minimum + maximum) / 2.0;
+    let half_span = (maximum - minimum) / 2.0;
+
+    let trace = move |time: f64| {
+        // Octaves of value noise, so the drift wanders on several time scales
+        let drift = 0.6 * value_noise(seed, time, 30.0)
+            + 0.3 * value_noise(seed.wrapping_add(1), time, 7.0)
+            + 0.1 * value_noise(seed.wrapping_add(2), time, 1.5);
+        let noise = value_noise(seed.wrapping_add(3), time, 0.05);
+        middle + half_span * (0.7 * drift + 0.05 * noise)
+    };
+    let base = Callback::new(trace, minimum, maximum, 32, true, 0.001, 0.0);
+
+    Box::new(Spikes::new(Box::new(base), 0.001, seed.wrapping_add(4)))
+}
+
+#[cfg(test)]
+mod presets_tests {
+    use super::*;
+    use crate::signal_type::generators::{sample_times, test_constant, NOISE_TEST_LOCK};
+
+    #[test]
+    fn presets_test() {
+        let presets: Vec<(Box<dyn Signal>, f64, f64)> = vec![
+            (Box::new(battery_voltage()), 80.0, 134.4),
+            (Box::new(motor_rpm()), -1500.0, 1500.0),
+            (Box::new(pack_temperature()), -20.0, 80.0),
+            (Box::new(motor_current()), -100.0, 100.0),
+        ];
+
+        for (signal, minimum, maximum) in presets {
+            for time in sample_times(0.0, 60.0, 0.1) {
+                let (value, _) = signal.calculate_pair(time);
+                assert!((minimum..=maximum).contains(&value), "{signal:?} at {time}");
+            }
+            assert!(signal.validate().is_empty());
+        }
+    }
+
+    #[test]
+    fn glitchy_test() {
+        let _lock = NOISE_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let base = || Constant {
+            minimum: -1000.0,
+            maximum: 1000.0,
+            ..test_constant(100.0)
+        };
+        let channel = |seed: u64| glitchy(Box::new(base()), 0.01, 0.05, seed);
+
+        let glitchy = channel(265);
+        let counts = |signal: &dyn Signal| {
+            sample_times(0.0, 60.0, 0.01)
+                .map(|time| signal.calculate_clean(time))
+                .fold((0, 0), |(spikes, dropouts), value| match value {
+                    0 => (spikes, dropouts + 1),
+                    100 => (spikes, dropouts),
+                    _ => (spikes + 1, dropouts),
+                })
+        };
+        let (spikes, dropouts) = counts(glitchy.as_ref());
+        assert!(spikes > 0);
+        assert!(dropouts > 0);
+
+        // The same seed gives the same faults and the same noise
+        assert_eq!(counts(channel(265).as_ref()), (spikes, dropouts));
+        let trace = |signal: &dyn Signal| -> Vec<i64> {
+            sample_times(0.0, 60.0, 0.01)
+                .map(|time| signal.calculate(time))
+                .collect()
+        };
+        let noisy = trace(glitchy.as_ref());
+        assert_eq!(noisy, trace(channel(265).as_ref()));
+        assert_ne!(noisy, trace(channel(266).as_ref()));
+        let clean: Vec<i64> = sample_times(0.0, 60.0, 0.01)
+            .map(|time| glitchy.calculate_clean(time))
+            .collect();
+        assert_ne!(noisy, clean);
+    }
+
+    #[test]
+    fn realistic_test() {
+        let trace = |seed: u64| -> Vec<i64> {
+            let signal = realistic((0.0, 100.0), seed);
+            sample_times(0.0, 120.0, 0.01)
+                .map(|time| signal.calculate(time))
+                .collect()
+        };
+
+        let first = trace(270);
+        assert_eq!(first, trace(270));
+        assert_ne!(first, trace(271));
+        assert!(first.iter().all(|value| (0..=100).contains(value)));
+        assert!(realistic((0.0, 100.0), 270).validate().is_empty());
+    }
+
+    #[test]
+    fn calibration_test() {
+        use crate::can_frame::ByteOrder;
+
+        for time in [0.0, 1.5, 1000.0] {
+            let frame = |signal: Constant| signal.calculate_frame(time, 0, ByteOrder::LittleEndian);
+            assert_eq!(frame(all_ones(8)), [0xFF, 0, 0, 0, 0, 0, 0, 0]);
+            assert_eq!(frame(all_zeros(8)), [0; 8]);
+            assert_eq!(frame(all_ones(12)), [0xFF, 0x0F, 0, 0, 0, 0, 0, 0]);
+        }
+    }
+}
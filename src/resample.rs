@@ -0,0 +1,81 @@
+use crate::dft::{dft, Complex};
+
+/// Resample a buffer of raw counts to `target_len` samples over the same span of time by
+/// zero-padding or truncating its spectrum
+///
+/// The buffer is treated as one period of a band-limited signal, so the frequencies it
+/// contains are preserved. When truncating, anything above the new Nyquist frequency is lost.
+pub fn resample_fft(samples: &[i64], target_len: usize) -> Vec<i64> {
+    let n = samples.len();
+    let m = target_len;
+    if n == 0 {
+        return vec![0; m];
+    }
+
+    let input: Vec<Complex> = samples.iter().map(|&count| (count as f64, 0.0)).collect();
+    let spectrum = dft(&input, false);
+
+    let shared = n.min(m);
+    let mut resized = vec![(0.0, 0.0); m];
+    let mut add = |frequency: isize, (re, im): Complex, weight: f64| {
+        let bin = frequency.rem_euclid(m as isize) as usize;
+        resized[bin].0 += re * weight;
+        resized[bin].1 += im * weight;
+    };
+
+    for (k, &bin) in spectrum.iter().enumerate() {
+        let frequency = if k <= n / 2 {
+            k as isize
+        } else {
+            k as isize - n as isize
+        };
+
+        if 2 * frequency.unsigned_abs() < shared {
+            add(frequency, bin, 1.0);
+        } else if 2 * frequency.unsigned_abs() == shared {
+            // The Nyquist bin of the shorter length is shared between +f and -f
+            if n < m {
+                add(frequency, bin, 0.5);
+                add(-frequency, bin, 0.5);
+            } else {
+                add(frequency, bin, 1.0);
+            }
+        }
+    }
+
+    dft(&resized, true)
+        .iter()
+        .map(|&(re, _)| (re / n as f64).round() as i64)
+        .collect()
+}
+
+#[cfg(test)]
+mod resample_tests {
+    use super::*;
+    use crate::signal_type::generators::{self, Signal};
+
+    #[test]
+    fn resample_fft_test() {
+        let sine = generators::test_sine(1000.0, 1.0);
+
+        // Four cycles in 64 samples
+        let samples: Vec<i64> = (0..64)
+            .map(|i| sine.calculate_clean(i as f64 / 16.0))
+            .collect();
+
+        // Still four cycles, now in 100 samples
+        let longer = resample_fft(&samples, 100);
+        assert_eq!(longer.len(), 100);
+        for (i, &count) in longer.iter().enumerate() {
+            let expected = sine.calculate_noiseless(i as f64 * 4.0 / 100.0);
+            assert!((count as f64 - expected).abs() <= 2.0, "{i}: {count}");
+        }
+
+        // And in 32 samples
+        let shorter = resample_fft(&samples, 32);
+        for (i, &count) in shorter.iter().enumerate() {
+            let expected = sine.calculate_noiseless(i as f64 * 4.0 / 32.0);
+            assert!((count as f64 - expected).abs() <= 2.0, "{i}: {count}");
+        }
+    }
+}
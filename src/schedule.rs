@@ -0,0 +1,85 @@
+use crate::signal_type::generators::{sample_times, Signal};
+
+/// A set of signals that are each transmitted at their own rate, as on a real bus
+#[derive(Debug, Default)]
+pub struct Schedule {
+    /// Each signal along with its transmit period in seconds
+    pub entries: Vec<(Box<dyn Signal>, f64)>,
+    /// Added to every exported time, e.g. a Unix timestamp to make the times absolute
+    pub epoch: f64,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Schedule::default()
+    }
+
+    /// Export absolute times, offset from `epoch` seconds
+    pub fn with_epoch(self, epoch: f64) -> Self {
+        Schedule { epoch, ..self }
+    }
+
+    /// Add a signal transmitted every `transmit_period` seconds, returning its index
+    pub fn add(&mut self, signal: Box<dyn Signal>, transmit_period: f64) -> usize {
+        self.entries.push((signal, transmit_period));
+        self.entries.len() - 1
+    }
+
+    /// Every transmission from `start` up to (but not including) `end` as (time, signal index, value),
+    /// ordered by time
+    ///
+    /// `start` and `end` are relative to the signals' t=0, but the exported times have the epoch
+    /// added. Transmissions at the same time are ordered by signal index
+    pub fn generate_scheduled(&self, start: f64, end: f64) -> Vec<(f64, usize, i64)> {
+        let mut events: Vec<(f64, usize, i64)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (signal, transmit_period))| {
+                sample_times(start, end, *transmit_period)
+                    .map(move |time| (self.epoch + time, index, signal.calculate(time)))
+            })
+            .collect();
+
+        // A stable sort keeps simultaneous transmissions in index order
+        events.sort_by(|a, b| a.0.total_cmp(&b.0));
+        events
+    }
+}
+
+#[cfg(test)]
+mod schedule_tests {
+    use super::*;
+    use crate::signal_type::generators;
+
+    #[test]
+    fn generate_scheduled_test() {
+        let sine = || generators::test_sine(100.0, 1.0);
+
+        let mut schedule = Schedule::new();
+        let slow = schedule.add(Box::new(sine()), 1.0);
+        let fast = schedule.add(Box::new(sine()), 0.1);
+
+        let events = schedule.generate_scheduled(0.0, 10.0);
+        let count = |index| events.iter().filter(|event| event.1 == index).count();
+        assert_eq!(count(slow), 10);
+        assert_eq!(count(fast), 100);
+
+        assert!(events.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+        assert_eq!((events[0].0, events[0].1), (0.0, slow));
+        assert_eq!((events[1].0, events[1].1), (0.0, fast));
+    }
+
+    #[test]
+    fn epoch_test() {
+        let constant = generators::test_constant(0.0);
+
+        let epoch = 1_700_000_000.0;
+        let mut schedule = Schedule::new().with_epoch(epoch);
+        schedule.add(Box::new(constant), 0.5);
+
+        let events = schedule.generate_scheduled(0.0, 2.0);
+        assert_eq!(events[0].0, epoch);
+        assert_eq!(events[3].0, epoch + 1.5);
+    }
+}
@@ -1,14 +1,17 @@
 use std::str::FromStr;
 
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::prelude::*;
 use rand::seq::SliceRandom;
 use rand::Rng;
 use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
-use serde::ser::{SerializeStruct, Serializer};
+use serde::ser::{self, SerializeStruct, Serializer};
 use serde::{Deserialize, Serialize};
 
 use crate::signal_type::generators::*;
 use crate::signal_type::*;
+use crate::signal_wrapper::{DcBlocker, MainsHum};
 
 #[pyclass]
 #[derive(Debug)]
@@ -26,27 +29,63 @@ pub const fn get_min_limit() -> f64 {
     i32::MIN as f64
 }
 
+/// The number of bits needed to represent `range` in steps of `resolution`
+///
+/// Always at least 1 bit, even if the range fits within a single step, and at most 64. A range
+/// or resolution that isn't a number gives no idea of the steps needed, so saturates at 64 bits.
+#[pyfunction]
+pub fn bits_for_resolution(range: f64, resolution: f64) -> u8 {
+    let steps = (range / resolution).abs();
+    if steps.is_nan() {
+        return 64;
+    }
+    steps.log2().ceil().clamp(1.0, 64.0) as u8
+}
+
+/// The scale that makes one LSB worth `lsb_value` engineering units
+///
+/// The scale is the value of one LSB, so this is the identity, but it says what is meant.
+/// Scales of 1 or more are applied as 1, see `Signal::verify_lsb`.
+#[pyfunction]
+pub fn scale_for_lsb(lsb_value: f64) -> f64 {
+    lsb_value
+}
+
+/// The period that makes `samples` samples taken at `sample_rate` cover exactly `target_cycles` cycles
+#[pyfunction]
+pub fn tune_period(target_cycles: u32, samples: usize, sample_rate: f64) -> f64 {
+    let duration = samples as f64 / sample_rate;
+    duration / target_cycles as f64
+}
+
+/// Globally disable (or re-enable) the noise added to every signal
+///
+/// See `generators::set_deterministic` for the thread-safety notes
+#[pyfunction]
+pub fn set_deterministic(enabled: bool) {
+    generators::set_deterministic(enabled)
+}
+
+/// Draw the noise for every signal from an RNG seeded with `seed`, or go back to unseeded
+/// noise if `seed` is `None`
+///
+/// See `generators::set_global_seed` for how this interacts with per-thread seeds
+#[pyfunction]
+pub fn set_global_seed(seed: Option<u64>) {
+    match seed {
+        Some(seed) => generators::set_global_seed(seed),
+        None => generators::clear_global_seed(),
+    }
+}
+
 fn calculate_minimum_and_maximum(
     is_signed: bool,
     num_bits: u8,
     scale: f64,
     offset: f64,
 ) -> (f64, f64) {
-    let (lvalue, rvalue) = {
-        if is_signed {
-            let min_by_bits = 1i64 << (num_bits - 1);
-            let lvalue = -1.0 * scale * min_by_bits as f64 + offset;
-            // avoid overflow in the following function
-            let max_by_bits = (1i64 << (num_bits - 1)) - 1;
-            let rvalue = scale * max_by_bits as f64 + offset;
-            (lvalue, rvalue)
-        } else {
-            let lvalue = offset;
-            let max_by_bits = (1 << num_bits) - 1;
-            let rvalue = scale * max_by_bits as f64 + offset;
-            (lvalue, rvalue)
-        }
-    };
+    let lvalue = scale * generators::min_count_for(num_bits, is_signed) as f64 + offset;
+    let rvalue = scale * generators::max_count_for(num_bits, is_signed) as f64 + offset;
 
     if lvalue > rvalue {
         (rvalue, lvalue)
@@ -58,6 +97,7 @@ fn calculate_minimum_and_maximum(
 #[pymethods]
 impl SignalGenerator {
     #[new]
+    #[allow(clippy::too_many_arguments)]
     #[pyo3(signature = (
         *,
         signal_type,
@@ -82,9 +122,11 @@ impl SignalGenerator {
         is_signed: bool,
         scale: f64,
         offset: f64,
-    ) -> Self {
+    ) -> PyResult<Self> {
         if minimum > maximum {
-            panic!("Minimum must be less than or equal to maximum");
+            return Err(PyValueError::new_err(
+                "Minimum must be less than or equal to maximum",
+            ));
         }
 
         if minimum == get_min_limit() && maximum == get_max_limit() {
@@ -95,72 +137,69 @@ impl SignalGenerator {
             maximum = calculate_minimum_and_maximum(is_signed, num_bits, scale, offset).1;
         }
 
-        let inner: Box<dyn Signal> = match signal_type {
-            SignalType::Sine => Box::new(Sine {
-                minimum,
-                maximum,
-                amplitude,
-                period,
-                phase,
-                num_bits,
-                is_signed,
-                scale,
-                offset,
-            }),
-            SignalType::Square => Box::new(Square {
-                minimum,
-                maximum,
-                amplitude,
-                period,
-                phase,
-                num_bits,
-                is_signed,
-                scale,
-                offset,
-            }),
-            SignalType::Triangle => Box::new(Triangle {
-                minimum,
-                maximum,
-                amplitude,
-                period,
-                phase,
-                num_bits,
-                is_signed,
-                scale,
-                offset,
-            }),
-            SignalType::Sawtooth => Box::new(Sawtooth {
-                minimum,
-                maximum,
-                amplitude,
-                period,
-                phase,
-                num_bits,
-                is_signed,
-                scale,
-                offset,
-            }),
-            SignalType::Constant => Box::new(Constant {
-                minimum,
-                maximum,
-                amplitude,
-                period,
-                phase,
-                num_bits,
-                is_signed,
-                scale,
-                offset,
-            }),
+        let params = SignalParams {
+            minimum,
+            maximum,
+            amplitude,
+            period,
+            phase,
+            num_bits,
+            is_signed,
+            scale,
+            offset,
         };
-        SignalGenerator { inner }
+        let inner = from_params(signal_type, &params);
+        Ok(SignalGenerator { inner })
     }
 
     pub fn calculate(&self, time: f64) -> i64 {
         self.inner.calculate(time)
     }
 
-    pub fn to_json(&self) -> String {
-        serde_json::to_string(self).unwrap()
+    /// Calculate the signal at every `step` from `start` up to (but not including) `end`
+    pub fn calculate_range(&self, start: f64, end: f64, step: f64) -> Vec<i64> {
+        self.inner.calculate_range(start, end, step)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.inner)
+    }
+
+    /// Compare the configurations of two plain waveforms
+    ///
+    /// Raises a ValueError for a wrapped or custom signal, whose configuration can't be compared,
+    /// and a TypeError for an ordering
+    fn __richcmp__(&self, other: &Self, op: CompareOp) -> PyResult<bool> {
+        match op {
+            CompareOp::Eq => self.try_eq(other),
+            CompareOp::Ne => self.try_eq(other).map(|equal| !equal),
+            _ => Err(PyTypeError::new_err("SignalGenerators have no order")),
+        }
+    }
+
+    /// A copy of the signal through a one-pole high-pass filter with a -3 dB cutoff of `cutoff`
+    /// Hz, emulating an AC-coupled channel
+    ///
+    /// Raises a ValueError for a wrapped or custom signal, which can't be copied
+    pub fn with_dc_blocker(&self, cutoff: f64) -> PyResult<Self> {
+        let inner: Box<dyn Signal> = Box::new(DcBlocker::new(self.plain_copy()?, cutoff));
+        Ok(SignalGenerator::from(inner))
+    }
+
+    /// A copy of the signal with mains hum added, a sine at `mains_freq` Hz whose amplitude is
+    /// `level` times the signal's
+    ///
+    /// Raises a ValueError for a wrapped or custom signal, which can't be copied
+    pub fn with_mains_hum(&self, mains_freq: f64, level: f64) -> PyResult<Self> {
+        let inner: Box<dyn Signal> = Box::new(MainsHum::new(self.plain_copy()?, mains_freq, level));
+        Ok(SignalGenerator::from(inner))
+    }
+
+    /// Turns the SignalGenerator into a JSON string
+    ///
+    /// Raises a ValueError for a wrapped or custom signal, which the JSON fields can't describe
+    pub fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(self).map_err(|e| PyValueError::new_err(e.to_string()))
     }
 
     #[staticmethod]
@@ -183,7 +222,7 @@ impl SignalGenerator {
         offset: f64,
         minimum: f64,
         maximum: f64,
-    ) -> Self {
+    ) -> PyResult<Self> {
         SignalGenerator::new(
             SignalType::Constant,
             minimum,
@@ -219,10 +258,10 @@ impl SignalGenerator {
         offset: f64,
         minimum: f64,
         maximum: f64,
-    ) -> Self {
+    ) -> PyResult<Self> {
         // Randomly choose a signal type
         let mut rng = rand::thread_rng();
-        let signal_type = SignalType::get_types().choose(&mut rng).unwrap().clone();
+        let signal_type = *SignalType::get_types().choose(&mut rng).unwrap();
         let amplitude = rng.gen_range(0.0..100.0);
         let period = rng.gen_range(0.0..10.0);
         let phase = rng.gen_range(0.0..period);
@@ -254,20 +293,49 @@ impl SignalGenerator {
     }
 }
 
+/// Allow any signal, including wrappers that have no `SignalType` of their own,
+/// to be handed to Python as a SignalGenerator
+///
+/// Only the plain waveforms can be serialized or compared, as the rest have no `to_params`
+impl From<Box<dyn Signal>> for SignalGenerator {
+    fn from(inner: Box<dyn Signal>) -> Self {
+        SignalGenerator { inner }
+    }
+}
+
+impl SignalGenerator {
+    /// A new plain waveform with the same configuration, or a ValueError for a wrapped or custom
+    /// signal, which has no configuration to build a copy from
+    fn plain_copy(&self) -> PyResult<Box<dyn Signal>> {
+        let (signal_type, params) = self.inner.to_params().ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "{:?} can't be copied, only plain waveforms can",
+                self.inner
+            ))
+        })?;
+        Ok(from_params(signal_type, &params))
+    }
+
+    /// Whether two plain waveforms have the same configuration, or a ValueError if either is a
+    /// wrapped or custom signal, whose params don't describe all of it
+    pub fn try_eq(&self, other: &Self) -> PyResult<bool> {
+        match (self.inner.to_params(), other.inner.to_params()) {
+            (Some(params), Some(other_params)) => Ok(params == other_params),
+            _ => Err(PyValueError::new_err(
+                "Only plain waveforms can be compared, not wrapped or custom signals",
+            )),
+        }
+    }
+}
+
 /// Allow SignalGenerator to be compared for equality
+///
+/// Plain waveforms are equal when their configurations are. A wrapped or custom signal has no
+/// configuration to compare, so is only equal to itself; `try_eq` refuses to compare them.
 impl PartialEq for SignalGenerator {
     fn eq(&self, other: &Self) -> bool {
-        // Compare all params
-        self.inner.get_type() == other.inner.get_type()
-            && self.inner.get_minimum() == other.inner.get_minimum()
-            && self.inner.get_maximum() == other.inner.get_maximum()
-            && self.inner.get_amplitude() == other.inner.get_amplitude()
-            && self.inner.get_period() == other.inner.get_period()
-            && self.inner.get_phase() == other.inner.get_phase()
-            && self.inner.get_num_bits() == other.inner.get_num_bits()
-            && self.inner.is_signed() == other.inner.is_signed()
-            && self.inner.get_scale() == other.inner.get_scale()
-            && self.inner.get_offset() == other.inner.get_offset()
+        self.try_eq(other)
+            .unwrap_or_else(|_| std::ptr::eq(self, other))
     }
 }
 
@@ -297,23 +365,29 @@ impl Serialize for SignalGenerator {
     ///
     /// # Returns
     ///
-    /// A Result containing the serialized `SignalGenerator` if successful, or an error if not
+    /// A Result containing the serialized `SignalGenerator` if successful, or an error if the
+    /// signal is wrapped or custom and so has no params to serialize
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let inner = self.inner.as_ref();
+        let (signal_type, params) = self.inner.to_params().ok_or_else(|| {
+            ser::Error::custom(format!(
+                "{:?} can't be serialized, only plain waveforms can",
+                self.inner
+            ))
+        })?;
         let mut state = serializer.serialize_struct("SignalGenerator", 10)?;
-        state.serialize_field("type", &inner.get_type_name())?;
-        state.serialize_field("minimum", &inner.get_minimum())?;
-        state.serialize_field("maximum", &inner.get_maximum())?;
-        state.serialize_field("amplitude", &inner.get_amplitude())?;
-        state.serialize_field("period", &inner.get_period())?;
-        state.serialize_field("phase", &inner.get_phase())?;
-        state.serialize_field("num_bits", &inner.get_num_bits())?;
-        state.serialize_field("is_signed", &inner.is_signed())?;
-        state.serialize_field("scale", &inner.get_scale())?;
-        state.serialize_field("offset", &inner.get_offset())?;
+        state.serialize_field("type", &signal_type.to_string())?;
+        state.serialize_field("minimum", &params.minimum)?;
+        state.serialize_field("maximum", &params.maximum)?;
+        state.serialize_field("amplitude", &params.amplitude)?;
+        state.serialize_field("period", &params.period)?;
+        state.serialize_field("phase", &params.phase)?;
+        state.serialize_field("num_bits", &params.num_bits)?;
+        state.serialize_field("is_signed", &params.is_signed)?;
+        state.serialize_field("scale", &params.scale)?;
+        state.serialize_field("offset", &params.offset)?;
         state.end()
     }
 }
@@ -403,7 +477,7 @@ impl<'de> Deserialize<'de> for SignalGenerator {
                     .next_element()?
                     .ok_or_else(|| de::Error::invalid_length(9, &self))?;
 
-                Ok(SignalGenerator::new(
+                SignalGenerator::new(
                     signal_type,
                     minimum,
                     maximum,
@@ -414,7 +488,8 @@ impl<'de> Deserialize<'de> for SignalGenerator {
                     is_signed,
                     scale,
                     offset,
-                ))
+                )
+                .map_err(de::Error::custom)
             }
 
             fn visit_map<V>(self, mut map: V) -> Result<SignalGenerator, V::Error>
@@ -440,12 +515,10 @@ impl<'de> Deserialize<'de> for SignalGenerator {
                                 return Err(de::Error::duplicate_field("type"));
                             }
 
-                            let parse_signal_type = SignalType::from_str(
-                                Some(map.next_value()?).expect("You need to specify a signal type"),
-                            );
+                            let parse_signal_type = SignalType::from_str(map.next_value()?);
 
                             // If the signal type is invalid / not parsable
-                            if !parse_signal_type.is_ok() {
+                            if parse_signal_type.is_err() {
                                 return Err(de::Error::custom("Invalid signal type"));
                             }
 
@@ -519,7 +592,7 @@ impl<'de> Deserialize<'de> for SignalGenerator {
                 let scale = scale.ok_or_else(|| de::Error::missing_field("scale"))?;
                 let offset = offset.ok_or_else(|| de::Error::missing_field("offset"))?;
 
-                Ok(SignalGenerator::new(
+                SignalGenerator::new(
                     signal_type,
                     minimum,
                     maximum,
@@ -530,11 +603,12 @@ impl<'de> Deserialize<'de> for SignalGenerator {
                     is_signed,
                     scale,
                     offset,
-                ))
+                )
+                .map_err(de::Error::custom)
             }
         }
 
-        const FIELDS: &'static [&'static str] = &[
+        const FIELDS: &[&str] = &[
             "type",
             "minimum",
             "maximum",
@@ -567,7 +641,8 @@ mod generation_tests {
 
         let default_signal = SignalGenerator::default_constant_signal(
             num_bits, is_signed, scale, offset, minimum, maximum,
-        );
+        )
+        .unwrap();
 
         assert_eq!(default_signal.inner.get_type(), SignalType::Constant);
         assert_eq!(default_signal.inner.get_num_bits(), num_bits);
@@ -577,6 +652,7 @@ mod generation_tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_random_generation() {
         let num_bits = 32;
         let is_signed = true;
@@ -587,7 +663,8 @@ mod generation_tests {
         let maximum = get_max_limit();
 
         let _random_signal =
-            SignalGenerator::random_signal(num_bits, is_signed, scale, offset, minimum, maximum);
+            SignalGenerator::random_signal(num_bits, is_signed, scale, offset, minimum, maximum)
+                .unwrap();
 
         assert!(true);
     }
@@ -603,7 +680,8 @@ mod generation_tests {
         let maximum = get_max_limit();
 
         let random_signal =
-            SignalGenerator::random_signal(num_bits, is_signed, scale, offset, minimum, maximum);
+            SignalGenerator::random_signal(num_bits, is_signed, scale, offset, minimum, maximum)
+                .unwrap();
 
         let mut rng = rand::thread_rng();
         for _i in 0..100 {
@@ -612,6 +690,193 @@ mod generation_tests {
             assert!(value as f64 / scale <= 65535.0);
         }
     }
+
+    #[test]
+    fn test_from_boxed_signal() {
+        let sine = SignalGenerator::new(
+            SignalType::Sine,
+            get_min_limit(),
+            get_max_limit(),
+            1000.0,
+            1.0,
+            0.0,
+            16,
+            true,
+            1.0,
+            0.0,
+        )
+        .unwrap();
+        let generator = sine.with_dc_blocker(0.01).unwrap();
+
+        let values = generator.calculate_range(0.0, 1.0, 0.01);
+        assert_eq!(values.len(), 100);
+        assert!(values.iter().any(|&value| value > 900));
+        assert!(generator.__repr__().starts_with("Signal"));
+
+        // The sine's fields alone don't describe the blocked signal, so it can't be serialized,
+        // copied or compared with anything but itself
+        assert!(serde_json::to_string(&generator).is_err());
+        assert!(generator.with_mains_hum(50.0, 0.1).is_err());
+        assert!(generator.try_eq(&sine).is_err());
+        assert!(generator.try_eq(&generator).is_err());
+        assert!(generator == generator);
+        assert!(generator != sine.with_dc_blocker(0.01).unwrap());
+
+        // The original is untouched
+        assert_eq!(sine.try_eq(&sine).ok(), Some(true));
+        assert!(
+            sine.with_mains_hum(50.0, 0.1)
+                .unwrap()
+                .inner
+                .effective_peak()
+                > 1000.0
+        );
+    }
+
+    #[test]
+    fn test_bits_for_resolution() {
+        assert_eq!(bits_for_resolution(100.0, 0.1), 10);
+        assert_eq!(bits_for_resolution(255.0, 1.0), 8);
+        assert_eq!(bits_for_resolution(0.5, 1.0), 1);
+        assert_eq!(bits_for_resolution(f64::NAN, 0.1), 64);
+        assert_eq!(bits_for_resolution(100.0, f64::NAN), 64);
+        assert_eq!(bits_for_resolution(0.0, 0.0), 64);
+    }
+
+    #[test]
+    fn test_verify_lsb() {
+        let signal = |scale: f64| {
+            SignalGenerator::new(
+                SignalType::Constant,
+                get_min_limit(),
+                get_max_limit(),
+                0.0,
+                1.0,
+                0.0,
+                16,
+                true,
+                scale,
+                0.0,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(signal(scale_for_lsb(0.01)).inner.verify_lsb(), 0.01);
+        assert_eq!(signal(1.0).inner.verify_lsb(), 1.0);
+        // Coarse scales are applied as 1
+        assert_eq!(signal(2.0).inner.verify_lsb(), 1.0);
+    }
+
+    #[test]
+    fn test_tune_period() {
+        let samples = 1000;
+        let sample_rate = 100.0;
+        let period = tune_period(7, samples, sample_rate);
+        assert!((period - 10.0 / 7.0).abs() < 1e-12);
+
+        let sawtooth = SignalGenerator::new(
+            SignalType::Sawtooth,
+            get_min_limit(),
+            get_max_limit(),
+            1000.0,
+            period,
+            0.0,
+            16,
+            true,
+            1.0,
+            0.0,
+        )
+        .unwrap();
+        let values = sawtooth.calculate_range(0.0, samples as f64 / sample_rate, 1.0 / sample_rate);
+        assert_eq!(values.len(), samples);
+
+        // Every cycle after the first starts with the sawtooth resetting
+        let resets = values
+            .windows(2)
+            .filter(|pair| pair[0] - pair[1] > 1000)
+            .count();
+        assert_eq!(resets + 1, 7);
+    }
+
+    #[test]
+    fn test_deterministic_mode() {
+        let _lock = NOISE_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let _deterministic = generators::Deterministic::enable();
+
+        for signal_type in SignalType::get_types() {
+            let signal = SignalGenerator::new(
+                signal_type,
+                get_min_limit(),
+                get_max_limit(),
+                1000.0,
+                10.0,
+                0.0,
+                16,
+                true,
+                1.0,
+                0.0,
+            )
+            .unwrap();
+
+            for i in 0..100 {
+                let time = i as f64 * 0.37;
+                assert_eq!(signal.calculate(time), signal.calculate(time));
+            }
+        }
+    }
+
+    #[test]
+    fn test_global_seed() {
+        let _lock = NOISE_TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let generate = || {
+            let signals: Vec<SignalGenerator> = SignalType::get_types()
+                .into_iter()
+                .map(|signal_type| {
+                    SignalGenerator::new(
+                        signal_type,
+                        get_min_limit(),
+                        get_max_limit(),
+                        1000.0,
+                        10.0,
+                        0.0,
+                        16,
+                        true,
+                        1.0,
+                        0.0,
+                    )
+                    .unwrap()
+                })
+                .collect();
+            (0..100)
+                .flat_map(|i| signals.iter().map(move |s| s.calculate(i as f64 * 0.37)))
+                .collect::<Vec<i64>>()
+        };
+
+        set_global_seed(Some(42));
+        let first = generate();
+        set_global_seed(Some(42));
+        let second = generate();
+        assert_eq!(first, second);
+
+        // A per-thread seed wins over the global one
+        let scoped = generators::with_seed(7, generate);
+        set_global_seed(None);
+        assert_eq!(scoped, generators::with_seed(7, generate));
+        assert_ne!(first, generate());
+
+        // The global lock is not held while the noise is drawn, so draws can nest
+        set_global_seed(Some(42));
+        let nested =
+            generators::with_rng(|rng| rng.next_u64() ^ generators::with_rng(|rng| rng.next_u64()));
+        set_global_seed(None);
+        assert_ne!(nested, 0);
+
+        // A panic inside with_seed still puts the unseeded RNG back
+        let panicked = std::panic::catch_unwind(|| generators::with_seed(7, || panic!()));
+        assert!(panicked.is_err());
+        assert_ne!(generators::with_seed(7, generate), generate());
+    }
 }
 
 #[cfg(test)]
@@ -659,7 +924,8 @@ mod serialization_tests {
                             is_signed,
                             scale,
                             offset,
-                        );
+                        )
+                        .unwrap();
 
                         assert_tokens(
                             &signal,
@@ -720,7 +986,8 @@ mod serialization_tests {
             is_signed,
             scale,
             offset,
-        );
+        )
+        .unwrap();
         assert_tokens(
             &signal,
             &[
@@ -18,6 +18,7 @@ pub enum SignalType {
 
 #[pymethods]
 impl SignalType {
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_string(&self) -> &'static str {
         match self {
             SignalType::Sine => "Sine",
@@ -48,6 +49,26 @@ impl SignalType {
         SignalType::iter().collect()
     }
 
+    /// The code the wire protocol uses for this type
+    ///
+    /// The codes are fixed and never reused: Sine 0, Square 1, Triangle 2, Sawtooth 3 and
+    /// Constant 4. New types take the next unused code, wherever they are declared.
+    pub fn to_code(&self) -> u8 {
+        match self {
+            SignalType::Sine => 0,
+            SignalType::Square => 1,
+            SignalType::Triangle => 2,
+            SignalType::Sawtooth => 3,
+            SignalType::Constant => 4,
+        }
+    }
+
+    /// The type with a given wire protocol code, if there is one
+    #[staticmethod]
+    pub fn from_code(code: u8) -> Option<SignalType> {
+        SignalType::iter().find(|signal_type| signal_type.to_code() == code)
+    }
+
     fn __repr__(&self) -> &'static str {
         self.to_string()
     }
@@ -55,10 +76,350 @@ impl SignalType {
 
 pub mod generators {
     use super::SignalType;
+    use crate::can_frame::{bit_positions, pack_bits, ByteOrder};
+    use crate::signal_generator::bits_for_resolution;
+    use crate::signal_wrapper::{DcBlocker, MainsHum};
 
     use core::fmt::Debug;
-    use rand::Rng;
+    use rand::rngs::{SmallRng, StdRng};
+    use rand::{Rng, RngCore, SeedableRng};
+    use serde::{Deserialize, Serialize};
+    use std::cell::RefCell;
     use std::f64::consts::PI;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    /// The noise is uniform within ±NOISE_LEVEL of the amplitude
+    pub const NOISE_LEVEL: f64 = 0.01;
+
+    /// When set, `noise()` returns 0 for every signal in the process
+    static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+
+    /// Serializes the tests that toggle or rely on the process-wide noise settings
+    #[cfg(test)]
+    pub(crate) static NOISE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    /// A 16 bit signed sine across the full limits, with no phase, scale or offset, for tests
+    #[cfg(test)]
+    pub(crate) fn test_sine(amplitude: f64, period: f64) -> Sine {
+        Sine {
+            minimum: crate::signal_generator::get_min_limit(),
+            maximum: crate::signal_generator::get_max_limit(),
+            amplitude,
+            period,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        }
+    }
+
+    /// A 16 bit signed constant across the full limits, with no scale or offset, for tests
+    #[cfg(test)]
+    pub(crate) fn test_constant(amplitude: f64) -> Constant {
+        Constant {
+            minimum: crate::signal_generator::get_min_limit(),
+            maximum: crate::signal_generator::get_max_limit(),
+            amplitude,
+            period: 0.0,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        }
+    }
+
+    /// Enable or disable deterministic mode, in which no signal adds noise
+    ///
+    /// The flag is a process-wide `AtomicBool`, so it is safe to toggle from any
+    /// thread, but it applies to the signals on every thread at once. A `calculate`
+    /// that is already running on another thread may see either setting.
+    pub fn set_deterministic(enabled: bool) {
+        DETERMINISTIC.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Whether deterministic mode is currently enabled
+    pub fn is_deterministic() -> bool {
+        DETERMINISTIC.load(Ordering::SeqCst)
+    }
+
+    /// Keeps deterministic mode enabled until dropped, including while a failing test unwinds,
+    /// so it can't leak into the tests that run after
+    #[cfg(test)]
+    pub(crate) struct Deterministic;
+
+    #[cfg(test)]
+    impl Deterministic {
+        pub(crate) fn enable() -> Self {
+            set_deterministic(true);
+            Deterministic
+        }
+    }
+
+    #[cfg(test)]
+    impl Drop for Deterministic {
+        fn drop(&mut self) {
+            set_deterministic(false);
+        }
+    }
+
+    thread_local! {
+        /// The RNG installed by `with_seed` for the calculations running on this thread
+        static SEEDED_RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+    }
+
+    /// Run `f` with all the noise on this thread drawn from an RNG seeded with `seed`,
+    /// so the noise it sees is reproducible
+    ///
+    /// The RNG that was installed before is put back when `f` returns or panics.
+    pub fn with_seed<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+        /// Puts the previous RNG back when dropped, including while unwinding
+        struct Restore(Option<StdRng>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let previous = self.0.take();
+                SEEDED_RNG.with(|rng| rng.replace(previous));
+            }
+        }
+
+        let _restore =
+            Restore(SEEDED_RNG.with(|rng| rng.replace(Some(StdRng::seed_from_u64(seed)))));
+        f()
+    }
+
+    /// The RNG installed by `set_global_seed`, shared by every thread
+    static GLOBAL_RNG: Mutex<Option<StdRng>> = Mutex::new(None);
+
+    /// Draw all the noise in the process from an RNG seeded with `seed`, so a whole batch of
+    /// signals is reproducible
+    ///
+    /// A seed set with `with_seed` takes priority over the global one on its thread. The global RNG
+    /// is shared, so signals calculated on several threads at once draw from it in whatever order
+    /// they happen to run.
+    pub fn set_global_seed(seed: u64) {
+        *GLOBAL_RNG.lock().unwrap_or_else(|e| e.into_inner()) = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /// Remove the RNG installed by `set_global_seed`, going back to `thread_rng`
+    pub fn clear_global_seed() {
+        *GLOBAL_RNG.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Draw random values from the RNG installed by `with_seed`, then the one installed by
+    /// `set_global_seed`, or from `thread_rng` if there is neither
+    ///
+    /// An installed RNG is only borrowed to draw a seed for a fresh `SmallRng` that `f` then uses,
+    /// so the global lock is not held while `f` runs and `f` may itself call `with_rng`.
+    pub fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        let seed = SEEDED_RNG
+            .with(|seeded| seeded.borrow_mut().as_mut().map(|rng| rng.next_u64()))
+            .or_else(|| {
+                GLOBAL_RNG
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .as_mut()
+                    .map(|rng| rng.next_u64())
+            });
+        match seed {
+            Some(seed) => f(&mut SmallRng::seed_from_u64(seed)),
+            None => f(&mut rand::thread_rng()),
+        }
+    }
+
+    /// The largest raw count that fits in `num_bits` bits
+    ///
+    /// The bits are limited to what an i64 can hold: 1 to 64 signed or 1 to 63 unsigned
+    pub fn max_count_for(num_bits: u8, is_signed: bool) -> i64 {
+        if is_signed {
+            i64::MAX >> (64 - num_bits.clamp(1, 64))
+        } else {
+            i64::MAX >> (63 - num_bits.clamp(1, 63))
+        }
+    }
+
+    /// The smallest raw count that fits in `num_bits` bits, limited as in `max_count_for`
+    pub fn min_count_for(num_bits: u8, is_signed: bool) -> i64 {
+        if is_signed {
+            i64::MIN >> (64 - num_bits.clamp(1, 64))
+        } else {
+            0
+        }
+    }
+
+    /// How far through its period a waveform is at a given time, from 0 up to (but not including) 1
+    ///
+    /// A period that is zero, negative or not finite has no cycle, so is always at the start
+    pub fn cycle_fraction(time: f64, phase: f64, period: f64) -> f64 {
+        if period > 0.0 && period.is_finite() {
+            ((time + phase) / period).rem_euclid(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// A sine of `amplitude` at a given time, with no noise
+    pub fn sine_value(amplitude: f64, period: f64, phase: f64, time: f64) -> f64 {
+        amplitude * (2.0 * PI * cycle_fraction(time, phase, period)).sin()
+    }
+
+    /// A square wave at `amplitude` for the first half of each period and `-amplitude` for the
+    /// second, with no noise
+    pub fn square_value(amplitude: f64, period: f64, phase: f64, time: f64) -> f64 {
+        if cycle_fraction(time, phase, period) < 0.5 {
+            amplitude
+        } else {
+            -amplitude
+        }
+    }
+
+    /// A triangle wave rising from 0 to `amplitude` over the first quarter of each period, with
+    /// no noise
+    pub fn triangle_value(amplitude: f64, period: f64, phase: f64, time: f64) -> f64 {
+        let t = cycle_fraction(time, phase, period);
+        if t < 0.25 {
+            amplitude * t * 4.0
+        } else if t < 0.75 {
+            amplitude * (1.0 - (t - 0.25) * 4.0)
+        } else {
+            amplitude * (t - 0.75) * 4.0 - amplitude
+        }
+    }
+
+    /// A sawtooth rising from `-amplitude` to `amplitude` over each period, with no noise
+    pub fn sawtooth_value(amplitude: f64, period: f64, phase: f64, time: f64) -> f64 {
+        amplitude * (cycle_fraction(time, phase, period) * 2.0 - 1.0)
+    }
+
+    /// A constant `amplitude`, with no noise
+    ///
+    /// Takes the same arguments as the other waveforms so they can be used interchangeably
+    pub fn constant_value(amplitude: f64, _period: f64, _phase: f64, _time: f64) -> f64 {
+        amplitude
+    }
+
+    /// The `order`th central moment of some samples over the `order`th power of their standard
+    /// deviation, NaN if they don't vary
+    fn standardized_moment(samples: &[f64], order: i32) -> f64 {
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / count;
+        let moment = |order| samples.iter().map(|x| (x - mean).powi(order)).sum::<f64>() / count;
+
+        let variance = moment(2);
+        if variance.is_nan() || variance <= 0.0 {
+            return f64::NAN;
+        }
+        moment(order) / variance.powf(order as f64 / 2.0)
+    }
+
+    /// A 64-bit FNV-1a hash, which unlike `DefaultHasher` is stable across Rust versions
+    fn fnv1a(bytes: impl Iterator<Item = u8>) -> u64 {
+        bytes.fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    /// A number from 0 up to (but not including) 1 that depends only on `seed` and `key`, for
+    /// random choices that must repeat exactly whenever the same time is calculated
+    pub fn hash_unit(seed: u64, key: f64) -> f64 {
+        let bytes = seed
+            .to_le_bytes()
+            .into_iter()
+            .chain(key.to_bits().to_le_bytes());
+        (fnv1a(bytes) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// The smallest "nice" step, 1, 2 or 5 times a power of 10, that is at least `resolution`
+    ///
+    /// A resolution that isn't positive and finite has no step, so is returned as it is
+    pub fn nice_step(resolution: f64) -> f64 {
+        if !(resolution > 0.0 && resolution.is_finite()) {
+            return resolution;
+        }
+        let decade = 10f64.powf(resolution.log10().floor());
+        [1.0, 2.0, 5.0, 10.0]
+            .into_iter()
+            .map(|mantissa| mantissa * decade)
+            // Allow for log10 landing just below a power of 10
+            .find(|&step| step >= resolution * (1.0 - 1e-9))
+            .unwrap_or(10.0 * decade)
+    }
+
+    /// The times from `start` up to (but not including) `end`, every `step` seconds
+    ///
+    /// Each time is computed from its index rather than accumulated, so long ranges don't drift
+    pub fn sample_times(start: f64, end: f64, step: f64) -> impl Iterator<Item = f64> {
+        let count = if step > 0.0 && end > start {
+            ((end - start) / step).ceil() as usize
+        } else {
+            0
+        };
+        (0..count)
+            .map(move |i| start + i as f64 * step)
+            .filter(move |&time| time < end)
+    }
+
+    /// Calculate every channel on each clock, as one row of values per clock time
+    ///
+    /// Channels wrapped in a `ConversionDelay` are sampled that long after each clock
+    pub fn channel_table(channels: &[&dyn Signal], clock_times: &[f64]) -> Vec<Vec<i64>> {
+        clock_times
+            .iter()
+            .map(|&time| {
+                channels
+                    .iter()
+                    .map(|channel| channel.calculate(time))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Calculate every channel at each `step` from `start` up to (but not including) `end`, as one
+    /// stream in round-robin order: each channel in turn at the first time, then at the next
+    pub fn interleave(channels: &[&dyn Signal], start: f64, end: f64, step: f64) -> Vec<i64> {
+        sample_times(start, end, step)
+            .flat_map(|time| channels.iter().map(move |channel| channel.calculate(time)))
+            .collect()
+    }
+
+    /// The phase of `a` relative to `b` in radians, wrapped to (-π, π]
+    ///
+    /// Only defined for two plain periodic waveforms (see `Signal::to_params`) with the same
+    /// period, otherwise `None`
+    pub fn phase_difference(a: &dyn Signal, b: &dyn Signal) -> Option<f64> {
+        let periodic = |signal: &dyn Signal| {
+            signal
+                .to_params()
+                .filter(|(signal_type, _)| *signal_type != SignalType::Constant)
+                .map(|(_, params)| params)
+        };
+        let (a, b) = (periodic(a)?, periodic(b)?);
+
+        let period = a.period;
+        if period <= 0.0 || period != b.period {
+            return None;
+        }
+
+        let difference = 2.0 * PI * (a.phase - b.phase) / period;
+        let wrapped = difference.rem_euclid(2.0 * PI);
+        Some(if wrapped > PI {
+            wrapped - 2.0 * PI
+        } else {
+            wrapped
+        })
+    }
+
+    /// Validate every named channel, returning the warnings of only the channels that have any,
+    /// in the order given
+    pub fn validate_all(signals: &[(&str, &dyn Signal)]) -> Vec<(String, Vec<SignalWarning>)> {
+        signals
+            .iter()
+            .map(|(name, signal)| (name.to_string(), signal.validate()))
+            .filter(|(_, warnings)| !warnings.is_empty())
+            .collect()
+    }
 
     /// A macro to create structs for each SignalType with the fields: amplitude, frequency, phase (all f64)
     macro_rules! signal_type_struct {
@@ -76,6 +437,47 @@ pub mod generators {
                     pub scale: f64,
                     pub offset: f64
                 }
+
+                impl $name {
+                    /// Set the scale and offset so the signal's bits exactly span `min_eng` to
+                    /// `max_eng`, and use that range as the minimum and maximum
+                    ///
+                    /// A scale is never applied above 1, so a range wider than the bits have
+                    /// counts is cut short: the bits span from `min_eng` one unit per count, and
+                    /// the maximum is lowered to the top of that
+                    pub fn with_engineering_range(self, min_eng: f64, max_eng: f64) -> Self {
+                        let counts = self.max_count() as f64 - self.min_count() as f64;
+                        let scale = ((max_eng - min_eng) / counts).min(1.0);
+                        let offset = min_eng / scale - self.min_count() as f64;
+
+                        $name {
+                            minimum: min_eng,
+                            maximum: max_eng.min(min_eng + counts * scale),
+                            scale,
+                            offset,
+                            ..self
+                        }
+                    }
+
+                    /// Set the phase so the waveform is `desired_phase_rad` radians through its
+                    /// cycle at `reference_time`
+                    ///
+                    /// A period that isn't positive and finite has no cycle, so leaves the phase
+                    /// as it is
+                    pub fn with_reference_phase(
+                        self,
+                        reference_time: f64,
+                        desired_phase_rad: f64,
+                    ) -> Self {
+                        if !(self.period > 0.0 && self.period.is_finite()) {
+                            return self;
+                        }
+                        let cycles = desired_phase_rad / (2.0 * PI);
+                        let phase = (cycles * self.period - reference_time).rem_euclid(self.period);
+
+                        $name { phase, ..self }
+                    }
+                }
             )*
         };
     }
@@ -112,13 +514,194 @@ pub mod generators {
             fn get_offset(&self) -> f64 {
                 self.offset
             }
+            fn to_params(&self) -> Option<(SignalType, SignalParams)> {
+                Some((
+                    SignalType::$name,
+                    SignalParams {
+                        minimum: self.minimum,
+                        maximum: self.maximum,
+                        amplitude: self.amplitude,
+                        period: self.period,
+                        phase: self.phase,
+                        num_bits: self.num_bits,
+                        is_signed: self.is_signed,
+                        scale: self.scale,
+                        offset: self.offset,
+                    },
+                ))
+            }
         };
     }
 
     // Create structs for each SignalType
     signal_type_struct!(Sine, Square, Triangle, Sawtooth, Constant);
 
+    /// The parameters shared by every generator, e.g. as loaded from a config file
+    #[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+    pub struct SignalParams {
+        pub minimum: f64,
+        pub maximum: f64,
+        pub amplitude: f64,
+        pub period: f64,
+        pub phase: f64,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+    }
+
+    /// Build the generator for a signal type from its parameters
+    pub fn from_params(signal_type: SignalType, params: &SignalParams) -> Box<dyn Signal> {
+        let SignalParams {
+            minimum,
+            maximum,
+            amplitude,
+            period,
+            phase,
+            num_bits,
+            is_signed,
+            scale,
+            offset,
+        } = *params;
+
+        macro_rules! build {
+            ($name:ident) => {
+                Box::new($name {
+                    minimum,
+                    maximum,
+                    amplitude,
+                    period,
+                    phase,
+                    num_bits,
+                    is_signed,
+                    scale,
+                    offset,
+                })
+            };
+        }
+
+        match signal_type {
+            SignalType::Sine => build!(Sine),
+            SignalType::Square => build!(Square),
+            SignalType::Triangle => build!(Triangle),
+            SignalType::Sawtooth => build!(Sawtooth),
+            SignalType::Constant => build!(Constant),
+        }
+    }
+
+    /// A configuration that is valid but probably not what was meant, as found by `validate`
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    pub enum SignalWarning {
+        /// The amplitude is smaller than one LSB, so the output barely changes
+        SubLsbAmplitude { amplitude: f64, lsb: f64 },
+    }
+
+    impl std::fmt::Display for SignalWarning {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                SignalWarning::SubLsbAmplitude { amplitude, lsb } => write!(
+                    f,
+                    "amplitude {amplitude} is smaller than one LSB ({lsb}), try a finer scale"
+                ),
+            }
+        }
+    }
+
+    /// Why a signal configuration couldn't be decoded by `decode_config`
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    pub enum ConfigDecodeError {
+        /// The encoding is the wrong length
+        WrongLength(usize),
+        /// The type byte isn't a known `SignalType` code
+        UnknownType(u8),
+    }
+
+    impl std::fmt::Display for ConfigDecodeError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                ConfigDecodeError::WrongLength(length) => write!(
+                    f,
+                    "expected {CONFIG_ENCODED_LEN} bytes of signal config, found {length}"
+                ),
+                ConfigDecodeError::UnknownType(code) => {
+                    write!(f, "unknown signal type code {code}")
+                }
+            }
+        }
+    }
+
+    impl std::error::Error for ConfigDecodeError {}
+
+    /// The length of an encoded config: the type, seven f64 fields, the bits and the signedness
+    pub const CONFIG_ENCODED_LEN: usize = 1 + 7 * 8 + 1 + 1;
+
+    /// Rebuild a signal from its `encode_config` encoding
+    pub fn decode_config(bytes: &[u8]) -> Result<Box<dyn Signal>, ConfigDecodeError> {
+        if bytes.len() != CONFIG_ENCODED_LEN {
+            return Err(ConfigDecodeError::WrongLength(bytes.len()));
+        }
+        let signal_type =
+            SignalType::from_code(bytes[0]).ok_or(ConfigDecodeError::UnknownType(bytes[0]))?;
+        let field = |i: usize| {
+            let start = 1 + 8 * i;
+            let mut field = [0; 8];
+            field.copy_from_slice(&bytes[start..start + 8]);
+            f64::from_le_bytes(field)
+        };
+
+        let params = SignalParams {
+            minimum: field(0),
+            maximum: field(1),
+            amplitude: field(2),
+            period: field(3),
+            phase: field(4),
+            scale: field(5),
+            offset: field(6),
+            num_bits: bytes[57],
+            is_signed: bytes[58] != 0,
+        };
+        Ok(from_params(signal_type, &params))
+    }
+
+    /// The minimal parameters needed to reconstruct a waveform's shape
+    ///
+    /// Only the fields that affect a given type are carried, e.g. a constant has no period or phase
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    pub enum WaveformSpec {
+        Sine {
+            amplitude: f64,
+            period: f64,
+            phase: f64,
+        },
+        Square {
+            amplitude: f64,
+            period: f64,
+            phase: f64,
+        },
+        Triangle {
+            amplitude: f64,
+            period: f64,
+            phase: f64,
+        },
+        Sawtooth {
+            amplitude: f64,
+            period: f64,
+            phase: f64,
+        },
+        Constant {
+            amplitude: f64,
+        },
+    }
+
+    /// A generated signal
+    ///
+    /// None of the provided methods panic for any configuration, even an invalid one: bit widths
+    /// outside what an i64 can hold are limited to it, inverted limits clamp to the maximum, and a
+    /// period that isn't positive holds the waveform at the start of its cycle
     pub trait Signal: Send {
+        /// The plain waveform the signal looks most like, for display
+        ///
+        /// Only the plain waveforms are the type they report, see `waveform`
         fn get_type(&self) -> SignalType;
         fn get_minimum(&self) -> f64;
         fn get_maximum(&self) -> f64;
@@ -134,45 +717,223 @@ pub mod generators {
             self.get_type().to_string()
         }
 
-        /// Shrink a value to only take up a certain number of bits
-        /// after the scale and offset have been applied
+        /// The type and parameters `from_params` rebuilds this exact signal from, if it is one of
+        /// the plain waveforms
         ///
-        /// Note: the number has to remain within the range of the signal's
-        /// minimum and maximum values
-        fn shrink_to_fit(&self, value: f64) -> i64 {
+        /// None for wrappers and the other generators, whose getters only describe part of them
+        fn to_params(&self) -> Option<(SignalType, SignalParams)> {
+            None
+        }
+
+        /// The plain waveform whose shape the signal follows, which the defaults below are worked
+        /// out from
+        ///
+        /// None for the other generators, which only report a type for display and override the
+        /// defaults they need instead. Wrappers report their inner signal's.
+        fn waveform(&self) -> Option<SignalType> {
+            self.to_params().map(|(signal_type, _)| signal_type)
+        }
+
+        /// The largest raw count that fits in the signal's bits
+        fn max_count(&self) -> i64 {
+            max_count_for(self.get_num_bits(), self.is_signed())
+        }
+
+        /// The smallest raw count that fits in the signal's bits
+        fn min_count(&self) -> i64 {
+            min_count_for(self.get_num_bits(), self.is_signed())
+        }
+
+        /// Clamp a value to the signal's minimum and maximum
+        ///
+        /// Unlike `f64::clamp` this never panics, even if the minimum is above the maximum
+        fn clamp_to_limits(&self, value: f64) -> f64 {
+            value.max(self.get_minimum()).min(self.get_maximum())
+        }
+
+        /// The engineering value of the largest raw count, i.e. `(max_count + offset) * scale`
+        ///
+        /// The scale is applied as `count_to_value` applies it, so scales of 1 or more count as 1
+        fn full_scale(&self) -> f64 {
+            self.count_to_value(self.max_count())
+        }
+
+        /// The engineering value of the smallest raw count, i.e. `(min_count + offset) * scale`
+        ///
+        /// The scale is applied as `count_to_value` applies it, so scales of 1 or more count as 1
+        fn zero_scale(&self) -> f64 {
+            self.count_to_value(self.min_count())
+        }
+
+        /// The waveform's type along with only the parameters relevant to it
+        ///
+        /// None if the signal isn't one of the plain waveforms (see `to_params`)
+        fn waveform_spec(&self) -> Option<WaveformSpec> {
+            let (signal_type, params) = self.to_params()?;
+            let amplitude = params.amplitude;
+            let period = params.period;
+            let phase = params.phase;
+
+            Some(match signal_type {
+                SignalType::Sine => WaveformSpec::Sine {
+                    amplitude,
+                    period,
+                    phase,
+                },
+                SignalType::Square => WaveformSpec::Square {
+                    amplitude,
+                    period,
+                    phase,
+                },
+                SignalType::Triangle => WaveformSpec::Triangle {
+                    amplitude,
+                    period,
+                    phase,
+                },
+                SignalType::Sawtooth => WaveformSpec::Sawtooth {
+                    amplitude,
+                    period,
+                    phase,
+                },
+                SignalType::Constant => WaveformSpec::Constant { amplitude },
+            })
+        }
+
+        /// Sample the signal on a master clock, holding the channel's last value at each tick
+        ///
+        /// The channel itself is only sampled every `sample_period` seconds, so a slow channel
+        /// repeats its most recent sample until its next update. `clock_times` must be sorted.
+        fn on_clock(&self, sample_period: f64, clock_times: &[f64]) -> Vec<i64> {
+            let mut last: Option<(i64, i64)> = None;
+
+            clock_times
+                .iter()
+                .map(|&time| {
+                    // Nudge by a tiny fraction so ticks landing exactly on a sample aren't floored down
+                    let index = (time / sample_period + 1e-9).floor() as i64;
+                    match last {
+                        Some((last_index, value)) if last_index == index => value,
+                        _ => {
+                            let value = self.calculate(index as f64 * sample_period);
+                            last = Some((index, value));
+                            value
+                        }
+                    }
+                })
+                .collect()
+        }
+
+        /// The factor between a raw count and its engineering value
+        ///
+        /// Scales of 1 or more are applied as 1, so coarse scales never lose integer precision
+        fn scale_factor(&self) -> f64 {
+            if self.get_scale() < 1.0 {
+                self.get_scale()
+            } else {
+                1.0
+            }
+        }
+
+        /// The raw count a value is stored as once the scale and offset have been removed,
+        /// clamped to the range of the number of bits
+        fn value_to_count(&self, value: f64) -> i64 {
             // Apply the reverse of the scale and offset
-            let clamped = value.max(self.get_minimum()).min(self.get_maximum());
-            let scale_factor = {
-                if self.get_scale() < 1.0 {
-                    self.get_scale()
-                } else {
-                    1.0
-                }
-            };
-            let scaled = clamped / scale_factor;
+            let clamped = self.clamp_to_limits(value);
+            let scaled = clamped / self.scale_factor();
             let offset = scaled - self.get_offset();
             let offset = offset.round() as i64;
 
-            let num_bits = self.get_num_bits();
-            let is_signed = self.is_signed();
+            // Clamp the value to the range of the number of bits
+            offset.max(self.min_count()).min(self.max_count())
+        }
 
-            let max_value = if is_signed {
-                2_i64.pow(num_bits as u32 - 1) - 1
-            } else {
-                2_i64.pow(num_bits as u32) - 1
-            };
+        /// The engineering value a raw count represents, undoing `value_to_count`
+        fn count_to_value(&self, count: i64) -> f64 {
+            (count as f64 + self.get_offset()) * self.scale_factor()
+        }
 
-            let min_value = if is_signed {
-                -(2_i64.pow(num_bits as u32 - 1))
-            } else {
-                0
+        /// A compact fixed-layout encoding of the configuration (not the samples), for sending over
+        /// a network and rebuilding with `decode_config`
+        ///
+        /// The type's protocol code, then the minimum, maximum, amplitude, period, phase, scale and
+        /// offset as little-endian f64s, then the bits and 1 if signed
+        ///
+        /// None if the signal has no `to_params`, as `decode_config` couldn't rebuild it
+        fn encode_config(&self) -> Option<Vec<u8>> {
+            let (signal_type, params) = self.to_params()?;
+
+            let mut bytes = Vec::with_capacity(CONFIG_ENCODED_LEN);
+            bytes.push(signal_type.to_code());
+            for field in [
+                params.minimum,
+                params.maximum,
+                params.amplitude,
+                params.period,
+                params.phase,
+                params.scale,
+                params.offset,
+            ] {
+                bytes.extend_from_slice(&field.to_le_bytes());
+            }
+            bytes.push(params.num_bits);
+            bytes.push(params.is_signed as u8);
+            Some(bytes)
+        }
+
+        /// Check the configuration for likely mistakes, returning a warning for each one found
+        fn validate(&self) -> Vec<SignalWarning> {
+            let mut warnings = Vec::new();
+
+            let amplitude = self.get_amplitude().abs();
+            let lsb = self.verify_lsb().abs();
+            if amplitude < lsb {
+                warnings.push(SignalWarning::SubLsbAmplitude { amplitude, lsb });
+            }
+
+            warnings
+        }
+
+        /// The worst-case magnitude of the noiseless waveform, including the effect of any wrappers,
+        /// for sizing bit fields
+        fn effective_peak(&self) -> f64 {
+            self.get_amplitude().abs()
+        }
+
+        /// The number of bits needed to store the waveform's full range with `headroom_fraction`
+        /// of it again spare, so occasional overshoots don't clip
+        fn recommended_bits(&self, headroom_fraction: f64) -> u8 {
+            let range = match self.waveform() {
+                Some(SignalType::Constant) => self.get_amplitude().abs(),
+                _ => 2.0 * self.get_amplitude().abs(),
             };
+            bits_for_resolution(range * (1.0 + headroom_fraction), self.verify_lsb())
+        }
 
-            // Clamp the value to the range of the number of bits
-            let clamped = offset.max(min_value).min(max_value);
+        /// The engineering value of one LSB with the scale as it is actually applied
+        fn verify_lsb(&self) -> f64 {
+            self.count_to_value(1) - self.count_to_value(0)
+        }
+
+        /// The clamped analog value at a given time, rounded to the nearest multiple of the
+        /// nice step (see `nice_step`) just coarser than one LSB, for display
+        fn snap_to_nice(&self, time: f64) -> f64 {
+            let value = self.clamp_to_limits(self.calculate_float(time));
+            let step = nice_step(self.verify_lsb().abs());
+            if step > 0.0 && step.is_finite() {
+                (value / step).round() * step
+            } else {
+                value
+            }
+        }
 
+        /// Shrink a value to only take up a certain number of bits
+        /// after the scale and offset have been applied
+        ///
+        /// Note: the number has to remain within the range of the signal's
+        /// minimum and maximum values
+        fn shrink_to_fit(&self, value: f64) -> i64 {
             // Undo the scale and offset
-            let clamped = (clamped as f64 + self.get_offset()) * scale_factor;
+            let clamped = self.count_to_value(self.value_to_count(value));
             let rounded = clamped.round() as i64;
 
             if rounded as f64 > self.get_maximum() {
@@ -185,142 +946,3757 @@ pub mod generators {
         }
 
         /// Calculates the fraction to use as the noise
+        ///
+        /// Always 0 while deterministic mode is enabled
         fn noise(&self) -> f64 {
-            static NOISE: f64 = 0.01;
-            let mut rng = rand::thread_rng();
-            rng.gen_range(-NOISE..NOISE)
+            if is_deterministic() {
+                return 0.0;
+            }
+            with_rng(|rng| rng.gen_range(-NOISE_LEVEL..NOISE_LEVEL))
         }
 
-        /// Calculate the value of the signal at a given time with noise
-        fn calculate(&self, time: f64) -> i64;
-    }
-
-    impl Debug for dyn Signal {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.debug_struct("Signal")
-                .field("type", &self.get_type_name())
-                .field("minimum", &self.get_minimum())
-                .field("maximum", &self.get_maximum())
-                .field("amplitude", &self.get_amplitude())
-                .field("period", &self.get_period())
-                .field("phase", &self.get_phase())
-                .field("num_bits", &self.get_num_bits())
-                .field("is_signed", &self.is_signed())
-                .field("scale", &self.get_scale())
-                .field("offset", &self.get_offset())
-                .finish()
+        /// The theoretical variance of the noise added to `calculate_float`
+        ///
+        /// Uniform noise over ±L has a variance of L²/3, where L is `NOISE_LEVEL * amplitude`
+        fn noise_variance(&self) -> f64 {
+            if is_deterministic() {
+                return 0.0;
+            }
+            let level = NOISE_LEVEL * self.get_amplitude();
+            level * level / 3.0
         }
-    }
 
-    impl Signal for Sine {
-        signal_type_getters!(Sine);
+        /// Calculate the analog value of the signal at a given time without any noise
+        fn calculate_noiseless(&self, time: f64) -> f64;
 
-        fn calculate(&self, time: f64) -> i64 {
-            let a = self.get_amplitude();
-            let b = 2.0 * PI / self.get_period();
-            let c = self.get_phase();
+        /// Calculate the analog value of the signal at a given time with noise,
+        /// before it is clamped and shrunk to fit
+        fn calculate_float(&self, time: f64) -> f64 {
+            self.calculate_noiseless(time) + self.noise() * self.get_amplitude()
+        }
 
-            let value = a * ((b * (time + c)).sin() + self.noise());
-            let value = value.clamp(self.minimum, self.maximum);
-            self.shrink_to_fit(value)
+        /// Calculate the signal at every `step` from `start` up to (but not including) `end`
+        fn calculate_range(&self, start: f64, end: f64, step: f64) -> Vec<i64> {
+            sample_times(start, end, step)
+                .map(|time| self.calculate(time))
+                .collect()
         }
-    }
 
-    impl Signal for Square {
-        signal_type_getters!(Square);
+        /// Calculate both the engineering value and the raw count of the signal at a given time
+        fn calculate_pair(&self, time: f64) -> (f64, i64) {
+            let value = self.calculate_float(time);
+            let value = self.clamp_to_limits(value);
+            let count = self.value_to_count(value);
+            (self.count_to_value(count), count)
+        }
 
-        fn calculate(&self, time: f64) -> i64 {
-            let value = {
-                if (time + self.phase) % self.period < self.period / 2.0 {
-                    self.amplitude
-                } else {
-                    -self.amplitude
-                }
-            };
-            let value = value + self.noise() * self.get_amplitude();
-            let value = value.clamp(self.minimum, self.maximum);
-            self.shrink_to_fit(value)
+        /// The effective number of bits, `(SNR - 1.76) / 6.02`, measured over a window
+        ///
+        /// The signal power is the variance of the noiseless waveform, and the noise power is
+        /// everything the output adds on top of it, both the noise and the quantization error
+        fn enob(&self, start: f64, end: f64, step: f64) -> f64 {
+            let (ideal, error): (Vec<f64>, Vec<f64>) = sample_times(start, end, step)
+                .map(|time| {
+                    let ideal = self.clamp_to_limits(self.calculate_noiseless(time));
+                    let (value, _) = self.calculate_pair(time);
+                    (ideal, value - ideal)
+                })
+                .unzip();
+
+            let count = ideal.len() as f64;
+            let mean = ideal.iter().sum::<f64>() / count;
+            let signal_power = ideal.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / count;
+            let noise_power = error.iter().map(|e| e * e).sum::<f64>() / count;
+
+            let snr_db = 10.0 * (signal_power / noise_power).log10();
+            (snr_db - 1.76) / 6.02
         }
-    }
 
-    impl Signal for Triangle {
-        signal_type_getters!(Triangle);
+        /// The noise-free code resolution, the number of bits whose codes stay steady despite the
+        /// noise, measured from `start` up to (but not including) `end`, every `step` seconds
+        ///
+        /// The peak-to-peak noise is taken as 6.6 times its RMS, which covers 99.9% of Gaussian
+        /// noise, so this is `num_bits - log2(6.6 * noise_rms / lsb)`, limited to between 0 and
+        /// `num_bits`. Without any noise every bit is noise-free.
+        fn noise_free_bits(&self, start: f64, end: f64, step: f64) -> f64 {
+            let (sum, count) = sample_times(start, end, step)
+                .map(|time| (self.calculate_float(time) - self.calculate_noiseless(time)).powi(2))
+                .fold((0.0, 0), |(sum, count), square| (sum + square, count + 1));
+            let noise_rms = (sum / count as f64).sqrt();
 
-        fn calculate(&self, time: f64) -> i64 {
-            let t = (time + self.phase) % self.period / self.period;
-            let value = {
-                if t < 0.25 {
-                    self.amplitude * t * 4.0
-                } else if t < 0.75 {
-                    self.amplitude * (1.0 - (t - 0.25) * 4.0)
-                } else {
-                    self.amplitude * (t - 0.75) * 4.0 - self.amplitude
+            let num_bits = self.get_num_bits() as f64;
+            let peak_to_peak_lsbs = 6.6 * noise_rms / self.verify_lsb().abs();
+            if peak_to_peak_lsbs > 1.0 {
+                (num_bits - peak_to_peak_lsbs.log2()).max(0.0)
+            } else {
+                num_bits
+            }
+        }
+
+        /// The RMS of the analog value, noise included, from `start` up to (but not including)
+        /// `end`, every `step` seconds
+        ///
+        /// The noise is independent of the waveform, so `rms_with_noise² ≈ rms_clean² + noise_variance`.
+        /// Run it inside `with_seed` for a reproducible result.
+        fn rms_with_noise(&self, start: f64, end: f64, step: f64) -> f64 {
+            let (sum, count) = sample_times(start, end, step)
+                .map(|time| self.calculate_float(time).powi(2))
+                .fold((0.0, 0), |(sum, count), square| (sum + square, count + 1));
+            (sum / count as f64).sqrt()
+        }
+
+        /// The standard deviation of the time between rising crossings of the period mean, from
+        /// `start` up to (but not including) `end`, sampled every `step` seconds
+        ///
+        /// Each crossing time is interpolated between the samples either side of it. NaN if there
+        /// are fewer than two full periods to compare, or the signal never starts another cycle.
+        fn period_jitter_std(&self, start: f64, end: f64, step: f64) -> f64 {
+            if self.time_to_next_period(start).is_infinite() {
+                return f64::NAN;
+            }
+            let midpoint = self.period_mean();
+            let samples: Vec<(f64, f64)> = sample_times(start, end, step)
+                .map(|time| (time, self.calculate_float(time) - midpoint))
+                .collect();
+
+            let crossings: Vec<f64> = samples
+                .windows(2)
+                .filter(|pair| pair[0].1 < 0.0 && pair[1].1 >= 0.0)
+                .map(|pair| {
+                    let ((t0, v0), (t1, v1)) = (pair[0], pair[1]);
+                    t0 + (t1 - t0) * -v0 / (v1 - v0)
+                })
+                .collect();
+            if crossings.len() < 3 {
+                return f64::NAN;
+            }
+
+            let periods: Vec<f64> = crossings.windows(2).map(|pair| pair[1] - pair[0]).collect();
+            let count = periods.len() as f64;
+            let mean = periods.iter().sum::<f64>() / count;
+            let variance = periods.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / count;
+            variance.sqrt()
+        }
+
+        /// The RMS of the analog value over the last `window_seconds` at each sample from `start` up
+        /// to (but not including) `end`, every `step` seconds, as (time, RMS)
+        ///
+        /// Until a full window has been sampled, and throughout if the window is longer than the
+        /// range, the RMS is over the samples so far
+        fn rolling_rms(
+            &self,
+            start: f64,
+            end: f64,
+            step: f64,
+            window_seconds: f64,
+        ) -> Vec<(f64, f64)> {
+            let window = ((window_seconds / step).round() as usize).max(1);
+            let samples: Vec<(f64, f64)> = sample_times(start, end, step)
+                .map(|time| (time, self.calculate_float(time).powi(2)))
+                .collect();
+
+            let mut sum = 0.0;
+            samples
+                .iter()
+                .enumerate()
+                .map(|(i, &(time, square))| {
+                    sum += square;
+                    if i >= window {
+                        sum -= samples[i - window].1;
+                    }
+                    let count = (i + 1).min(window);
+                    (time, (sum.max(0.0) / count as f64).sqrt())
+                })
+                .collect()
+        }
+
+        /// The magnitude-weighted mean frequency of the analog value's spectrum, in Hz, from `start`
+        /// up to (but not including) `end`, sampled every `step` seconds
+        ///
+        /// The DC bin is left out, so a constant offset doesn't drag the centroid towards 0 Hz
+        #[cfg(feature = "spectral")]
+        fn spectral_centroid(&self, start: f64, end: f64, step: f64) -> f64 {
+            let samples: Vec<(f64, f64)> = sample_times(start, end, step)
+                .map(|time| (self.calculate_float(time), 0.0))
+                .collect();
+            let spectrum = crate::dft::dft(&samples, false);
+
+            let n = samples.len();
+            let (weighted, total) = spectrum
+                .iter()
+                .enumerate()
+                .take(n / 2 + 1)
+                .skip(1)
+                .map(|(k, &(re, im))| (k as f64 / (n as f64 * step), re.hypot(im)))
+                .fold((0.0, 0.0), |(weighted, total), (frequency, magnitude)| {
+                    (weighted + frequency * magnitude, total + magnitude)
+                });
+            weighted / total
+        }
+
+        /// The `k` largest peaks in the spectrum of the analog value from `start` up to (but not
+        /// including) `end`, sampled every `step` seconds, as `(frequency, magnitude)` pairs from
+        /// the largest down
+        ///
+        /// A peak is a bin at least as large as both its neighbours, and its magnitude is the
+        /// amplitude of a sine exactly at that bin. The DC bin is left out, like
+        /// `spectral_centroid`, and fewer than `k` peaks are returned if there aren't enough.
+        #[cfg(feature = "spectral")]
+        fn spectral_peaks(&self, start: f64, end: f64, step: f64, k: usize) -> Vec<(f64, f64)> {
+            let samples: Vec<(f64, f64)> = sample_times(start, end, step)
+                .map(|time| (self.calculate_float(time), 0.0))
+                .collect();
+            let spectrum = crate::dft::dft(&samples, false);
+
+            let n = samples.len();
+            let magnitudes: Vec<f64> = spectrum
+                .iter()
+                .take(n / 2 + 1)
+                .map(|&(re, im)| 2.0 * re.hypot(im) / n as f64)
+                .collect();
+
+            let mut peaks: Vec<(f64, f64)> = (1..magnitudes.len())
+                .filter(|&bin| {
+                    let next = magnitudes.get(bin + 1).copied().unwrap_or(0.0);
+                    magnitudes[bin] >= magnitudes[bin - 1] && magnitudes[bin] >= next
+                })
+                .map(|bin| (bin as f64 / (n as f64 * step), magnitudes[bin]))
+                .collect();
+            peaks.sort_by(|a, b| b.1.total_cmp(&a.1));
+            peaks.truncate(k);
+            peaks
+        }
+
+        /// The normalized autocorrelation of the analog value from `start` up to (but not including)
+        /// `end`, sampled every `step` seconds, for each lag from 0 to `max_lag` samples
+        ///
+        /// The mean is removed first, and lag 0 is always 1 unless the window is flat
+        fn autocorrelation(&self, start: f64, end: f64, step: f64, max_lag: usize) -> Vec<f64> {
+            let samples: Vec<f64> = sample_times(start, end, step)
+                .map(|time| self.calculate_float(time))
+                .collect();
+            let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+            let centered: Vec<f64> = samples.iter().map(|x| x - mean).collect();
+            let energy: f64 = centered.iter().map(|x| x * x).sum();
+
+            (0..=max_lag)
+                .map(|lag| {
+                    let sum: f64 = centered
+                        .iter()
+                        .zip(centered.iter().skip(lag))
+                        .map(|(a, b)| a * b)
+                        .sum();
+                    sum / energy
+                })
+                .collect()
+        }
+
+        /// The coefficient of variation (standard deviation over mean) of the analog value
+        /// from `start` up to (but not including) `end`, every `step` seconds
+        ///
+        /// NaN if the mean is within one LSB (`verify_lsb`) of zero, where it can't be told apart
+        /// from zero and the ratio means nothing
+        fn coefficient_of_variation(&self, start: f64, end: f64, step: f64) -> f64 {
+            let samples: Vec<f64> = sample_times(start, end, step)
+                .map(|time| self.calculate_float(time))
+                .collect();
+
+            let count = samples.len() as f64;
+            let mean = samples.iter().sum::<f64>() / count;
+            if mean.abs() < self.verify_lsb().abs() {
+                return f64::NAN;
+            }
+            let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / count;
+
+            variance.sqrt() / mean
+        }
+
+        /// The skewness (third standardized moment) of the analog value from `start` up to (but
+        /// not including) `end`, every `step` seconds
+        ///
+        /// 0 for symmetric noise, positive when the values trail off further above the mean than
+        /// below it. NaN if the values don't vary.
+        fn skewness(&self, start: f64, end: f64, step: f64) -> f64 {
+            let samples: Vec<f64> = sample_times(start, end, step)
+                .map(|time| self.calculate_float(time))
+                .collect();
+            standardized_moment(&samples, 3)
+        }
+
+        /// The kurtosis (fourth standardized moment) of the analog value from `start` up to (but
+        /// not including) `end`, every `step` seconds
+        ///
+        /// 3 for Gaussian noise, 1.8 for uniform noise and larger for impulsive noise. NaN if the
+        /// values don't vary.
+        fn kurtosis(&self, start: f64, end: f64, step: f64) -> f64 {
+            let samples: Vec<f64> = sample_times(start, end, step)
+                .map(|time| self.calculate_float(time))
+                .collect();
+            standardized_moment(&samples, 4)
+        }
+
+        /// Calculate the analog value at a given time in signed Qm.n fixed point, with `int_bits`
+        /// integer bits and `frac_bits` fractional bits plus a sign bit, saturating on overflow
+        fn calculate_q(&self, time: f64, int_bits: u8, frac_bits: u8) -> i64 {
+            let value = self.clamp_to_limits(self.calculate_float(time));
+            let bits = int_bits.saturating_add(frac_bits).saturating_add(1);
+            let encoded = (value * 2_f64.powi(frac_bits as i32)).round() as i64;
+            encoded
+                .max(min_count_for(bits, true))
+                .min(max_count_for(bits, true))
+        }
+
+        /// The samples from `start` up to (but not including) `end`, every `step` seconds, that
+        /// differ from the last one emitted by more than `change_threshold` counts, as (time, value)
+        ///
+        /// The first sample is always emitted
+        fn delta_samples(
+            &self,
+            start: f64,
+            end: f64,
+            step: f64,
+            change_threshold: i64,
+        ) -> Vec<(f64, i64)> {
+            let mut emitted: Vec<(f64, i64)> = Vec::new();
+            for time in sample_times(start, end, step) {
+                let value = self.calculate(time);
+                match emitted.last() {
+                    Some(&(_, last)) if (value - last).abs() <= change_threshold => {}
+                    _ => emitted.push((time, value)),
+                }
+            }
+            emitted
+        }
+
+        /// How many different raw counts the output takes from `start` up to (but not including)
+        /// `end`, sampled every `step` seconds
+        ///
+        /// A waveform that only spans part of its field uses far fewer than `2^num_bits` codes
+        fn distinct_levels(&self, start: f64, end: f64, step: f64) -> usize {
+            sample_times(start, end, step)
+                .map(|time| self.calculate_pair(time).1)
+                .collect::<std::collections::HashSet<i64>>()
+                .len()
+        }
+
+        /// How many times a second the analog value crosses `threshold`, in either direction,
+        /// from `start` up to (but not including) `end`, sampled every `step` seconds
+        ///
+        /// A periodic signal crosses its midpoint twice a period, so this is twice its frequency
+        fn crossing_rate(&self, start: f64, end: f64, step: f64, threshold: f64) -> f64 {
+            let above: Vec<bool> = sample_times(start, end, step)
+                .map(|time| self.calculate_float(time) >= threshold)
+                .collect();
+            let crossings = above.windows(2).filter(|pair| pair[0] != pair[1]).count();
+            crossings as f64 / (end - start)
+        }
+
+        /// Calculate the raw count at a given time as it would arrive over a noisy link, with each of
+        /// its bits flipped with probability `bit_error_rate`
+        ///
+        /// The flips are random on every call, even in deterministic mode, so seed them with
+        /// `with_seed` to reproduce them
+        fn calculate_with_bit_errors(&self, time: f64, bit_error_rate: f64) -> i64 {
+            let (_, count) = self.calculate_pair(time);
+            let bits = self.get_num_bits().clamp(1, 64) as u32;
+            let probability = bit_error_rate.clamp(0.0, 1.0);
+
+            let errors = with_rng(|rng| {
+                (0..bits).fold(0u64, |errors, bit| {
+                    if rng.gen_bool(probability) {
+                        errors | 1 << bit
+                    } else {
+                        errors
+                    }
+                })
+            });
+
+            // Flip within the signal's bits, then read them back as a count
+            let unused = 64 - bits;
+            let received = ((count as u64) ^ errors) << unused;
+            if self.is_signed() {
+                (received as i64) >> unused
+            } else {
+                (received >> unused) as i64
+            }
+        }
+
+        /// Calculate the raw count at a given time, reduced to `target_bits` with triangular (TPDF)
+        /// dither so the quantization error is decorrelated from the signal
+        ///
+        /// The dither is random on every call, even in deterministic mode
+        fn dither_to_bits(&self, time: f64, target_bits: u8) -> i64 {
+            let value = self.clamp_to_limits(self.calculate_float(time));
+            let count = value / self.scale_factor() - self.get_offset();
+
+            let shift = self.get_num_bits().saturating_sub(target_bits).min(63) as u32;
+            let reduced = count / 2_f64.powi(shift as i32);
+
+            let dither = with_rng(|rng| rng.gen_range(-0.5..0.5) + rng.gen_range(-0.5..0.5));
+
+            let dithered = (reduced + dither).round() as i64;
+            dithered.clamp(self.min_count() >> shift, self.max_count() >> shift)
+        }
+
+        /// A checksum of the window's values with the noise drawn from an RNG seeded with `seed`
+        ///
+        /// The checksum is stable across runs and versions for identical configurations. Stateful
+        /// wrappers carry their state between calls, so should be freshly built for each checksum.
+        fn window_checksum(&self, start: f64, end: f64, step: f64, seed: u64) -> u64 {
+            let values = with_seed(seed, || self.calculate_range(start, end, step));
+            fnv1a(values.iter().flat_map(|value| value.to_le_bytes()))
+        }
+
+        /// Calculate at most `max_samples` evenly spaced samples covering `start` to `end`,
+        /// along with the step that was used between them
+        ///
+        /// A budget of zero returns no samples and a step of zero
+        fn calculate_budgeted(&self, start: f64, end: f64, max_samples: usize) -> (Vec<i64>, f64) {
+            if max_samples == 0 {
+                return (Vec::new(), 0.0);
+            }
+
+            let step = (end - start) / max_samples as f64;
+            let mut values = self.calculate_range(start, end, step);
+            values.truncate(max_samples);
+            (values, step)
+        }
+
+        /// Calculate the engineering values and raw counts at every `step` from `start` up to
+        /// (but not including) `end`, in a single pass
+        fn calculate_range_pairs(&self, start: f64, end: f64, step: f64) -> (Vec<f64>, Vec<i64>) {
+            sample_times(start, end, step)
+                .map(|time| self.calculate_pair(time))
+                .unzip()
+        }
+
+        /// Calculate the signal without noise and with noise drawn from an RNG seeded with
+        /// `seed`, at every `step` from `start` up to (but not including) `end`
+        ///
+        /// The clean trace is the ground truth for the noisy one, so a filter run over the noisy
+        /// trace can be scored against it sample by sample
+        fn calculate_range_clean_and_noisy(
+            &self,
+            start: f64,
+            end: f64,
+            step: f64,
+            seed: u64,
+        ) -> (Vec<i64>, Vec<i64>) {
+            let clean = sample_times(start, end, step)
+                .map(|time| self.calculate_clean(time))
+                .collect();
+            let noisy = with_seed(seed, || self.calculate_range(start, end, step));
+            (clean, noisy)
+        }
+
+        /// Calculate the signal at a given time and pack its raw count into an 8 byte CAN frame,
+        /// occupying `num_bits` bits from `start_bit` in the given byte order
+        fn calculate_frame(&self, time: f64, start_bit: u16, byte_order: ByteOrder) -> [u8; 8] {
+            let (_, count) = self.calculate_pair(time);
+            let mut frame = [0; 8];
+            pack_bits(
+                &mut frame,
+                count as u64,
+                start_bit,
+                self.get_num_bits(),
+                byte_order,
+            );
+            frame
+        }
+
+        /// A human readable breakdown of packing the raw count `value` into an 8 byte CAN frame the
+        /// way `calculate_frame` does: where its least and most significant bits land, which bytes
+        /// it spans, and the resulting frame in hex
+        fn packing_debug(&self, value: i64, bit_start: u16, byte_order: ByteOrder) -> String {
+            let num_bits = self.get_num_bits();
+            let mut frame = [0u8; 8];
+            pack_bits(&mut frame, value as u64, bit_start, num_bits, byte_order);
+
+            let positions = bit_positions(bit_start, num_bits, byte_order);
+            let (lsb, msb) = match byte_order {
+                ByteOrder::LittleEndian => (positions.first(), positions.last()),
+                ByteOrder::BigEndian => (positions.last(), positions.first()),
+            };
+            let describe = |position: Option<&usize>| match position {
+                Some(&position) if position / 8 < frame.len() => {
+                    format!(
+                        "bit {position} (byte {} bit {})",
+                        position / 8,
+                        position % 8
+                    )
                 }
+                Some(&position) => format!("bit {position} (outside the frame, dropped)"),
+                None => "none".to_string(),
+            };
+            let first_byte = positions.iter().min().map_or(0, |position| position / 8);
+            let last_byte = positions.iter().max().map_or(0, |position| position / 8);
+
+            // Wider than 64 bits shows the whole u64
+            let mask = u64::MAX
+                .checked_shr(64u32.saturating_sub(num_bits as u32))
+                .unwrap_or(0);
+            let hex: Vec<String> = frame.iter().map(|byte| format!("{byte:02X}")).collect();
+            format!(
+                "value {value} (0x{:X}) in {num_bits} bits, {byte_order:?}\n\
+                 LSB at {}, MSB at {}, bytes {first_byte}-{last_byte}\n\
+                 frame: {}",
+                value as u64 & mask,
+                describe(lsb),
+                describe(msb),
+                hex.join(" "),
+            )
+        }
+
+        /// Wrap the signal in a high-pass filter that removes its DC component
+        fn with_dc_blocker(self, cutoff: f64) -> DcBlocker
+        where
+            Self: Sized + 'static,
+        {
+            DcBlocker::new(Box::new(self), cutoff)
+        }
+
+        /// Add mains hum at `mains_freq` Hz, with an amplitude of `level` times the signal's
+        fn with_mains_hum(self, mains_freq: f64, level: f64) -> MainsHum
+        where
+            Self: Sized + 'static,
+        {
+            MainsHum::new(Box::new(self), mains_freq, level)
+        }
+
+        /// The exact mean of the noiseless waveform over one period
+        ///
+        /// The periodic waveforms are all symmetric about zero, so only a constant has a DC component.
+        /// NaN for signals whose mean can't be known without calculating them.
+        fn period_mean(&self) -> f64 {
+            match self.waveform() {
+                Some(
+                    SignalType::Sine
+                    | SignalType::Square
+                    | SignalType::Triangle
+                    | SignalType::Sawtooth,
+                ) => 0.0,
+                Some(SignalType::Constant) => self.get_amplitude(),
+                None => f64::NAN,
+            }
+        }
+
+        /// How long from `time` until the next period boundary, when the next cycle starts, in
+        /// seconds
+        ///
+        /// Exactly on a boundary the cycle has just started, so this is a whole period. A signal
+        /// with no cycle never reaches one, so this is infinite.
+        fn time_to_next_period(&self, time: f64) -> f64 {
+            let period = self.get_period();
+            if self.waveform() == Some(SignalType::Constant)
+                || !(period > 0.0 && period.is_finite())
+            {
+                return f64::INFINITY;
+            }
+            period * (1.0 - cycle_fraction(time, self.get_phase(), period))
+        }
+
+        /// The largest rate of change of the noiseless waveform, in engineering units per second
+        ///
+        /// The instantaneous jumps of a square wave's edges and a sawtooth's reset are ignored, so
+        /// this is the slope of the sawtooth's ramp and 0 for a square wave. NaN for a signal that
+        /// isn't a plain waveform and doesn't say.
+        fn max_slew_rate(&self) -> f64 {
+            let Some(waveform) = self.waveform() else {
+                return f64::NAN;
+            };
+            let amplitude = self.get_amplitude().abs();
+            let period = self.get_period();
+            if period <= 0.0 {
+                return 0.0;
+            }
+
+            match waveform {
+                SignalType::Sine => amplitude * 2.0 * PI / period,
+                SignalType::Triangle => amplitude * 4.0 / period,
+                SignalType::Sawtooth => amplitude * 2.0 / period,
+                SignalType::Square | SignalType::Constant => 0.0,
+            }
+        }
+
+        /// Calculate the value of the signal at a given time without noise
+        fn calculate_clean(&self, time: f64) -> i64 {
+            let value = self.calculate_noiseless(time);
+            let value = self.clamp_to_limits(value);
+            self.shrink_to_fit(value)
+        }
+
+        /// The noiseless value at 0, 25, 50, 75 and 100% of the first period, as (percent, value)
+        fn period_table(&self) -> Vec<(f64, i64)> {
+            [0.0, 25.0, 50.0, 75.0, 100.0]
+                .iter()
+                .map(|&percent| {
+                    let time = self.get_period() * percent / 100.0;
+                    (percent, self.calculate_clean(time))
+                })
+                .collect()
+        }
+
+        /// Whether two signals produce identical noiseless output over a window,
+        /// even if their parameters differ
+        fn produces_same_output(
+            &self,
+            other: &dyn Signal,
+            start: f64,
+            end: f64,
+            step: f64,
+        ) -> bool {
+            sample_times(start, end, step)
+                .all(|time| self.calculate_clean(time) == other.calculate_clean(time))
+        }
+
+        /// The fluctuating (AC) part of the analog value at a given time, with the period mean removed
+        ///
+        /// If the period mean is NaN, the running mean of the noiseless waveform from 0 to `time`
+        /// is removed instead. That is sampled from 0 again on every call, so a stateful wrapper
+        /// sees its time go backwards.
+        fn ac_component(&self, time: f64) -> f64 {
+            let mean = self.period_mean();
+            if !mean.is_nan() {
+                return self.calculate_float(time) - mean;
+            }
+
+            const SAMPLES: usize = 1000;
+            let running_mean = if time > 0.0 {
+                (0..=SAMPLES)
+                    .map(|i| self.calculate_noiseless(time * i as f64 / SAMPLES as f64))
+                    .sum::<f64>()
+                    / (SAMPLES + 1) as f64
+            } else {
+                self.calculate_noiseless(time)
+            };
+            self.calculate_float(time) - running_mean
+        }
+
+        /// The noiseless waveform over its second period, sampled a thousand times, starting a
+        /// quarter period early so a rising edge at the period boundary is inside it
+        ///
+        /// The first period is calculated first, so stateful wrappers have settled
+        fn edge_window(&self) -> Vec<(f64, f64)> {
+            let period = self.get_period();
+            let step = period / 1000.0;
+            let start = 0.75 * period;
+            sample_times(0.0, start, step).for_each(|time| {
+                self.calculate_noiseless(time);
+            });
+            sample_times(start, start + period, step)
+                .map(|time| (time, self.calculate_noiseless(time)))
+                .collect()
+        }
+
+        /// The time the rising edge takes to go from `low_pct` to `high_pct` percent of the way
+        /// from the low level (`-amplitude`) to the high level (`amplitude`), in seconds
+        ///
+        /// Measured to a thousandth of a period, so an ideal edge rises in at most that.
+        /// NaN if the signal has no rising edge.
+        fn rise_time(&self, low_pct: f64, high_pct: f64) -> f64 {
+            let amplitude = self.get_amplitude().abs();
+            let level = |pct: f64| -amplitude + 2.0 * amplitude * pct / 100.0;
+            let (low, high) = (level(low_pct), level(high_pct));
+
+            let window = self.edge_window();
+            let crossing = |threshold: f64, from: usize| {
+                (from.max(1)..window.len())
+                    .find(|&i| window[i - 1].1 < threshold && window[i].1 >= threshold)
+            };
+            let Some(start) = crossing(low, 0) else {
+                return f64::NAN;
+            };
+            let Some(end) = crossing(high, start) else {
+                return f64::NAN;
+            };
+            window[end].0 - window[start].0
+        }
+
+        /// How far the waveform goes past its high level (`amplitude`), as a fraction of the swing
+        /// from the low level to the high level
+        ///
+        /// Measured over the same period as `rise_time`. 0 if it never goes past.
+        fn overshoot(&self) -> f64 {
+            let amplitude = self.get_amplitude().abs();
+            let peak = self
+                .edge_window()
+                .iter()
+                .fold(f64::NEG_INFINITY, |peak, &(_, value)| peak.max(value));
+            ((peak - amplitude) / (2.0 * amplitude)).max(0.0)
+        }
+
+        /// The smallest and largest value from `start` up to (but not including) `end`, sampled
+        /// every `step` seconds, in one pass without keeping the samples
+        ///
+        /// An empty window gives `(i64::MAX, i64::MIN)`, so merging it with another changes nothing
+        fn window_extremes(&self, start: f64, end: f64, step: f64) -> (i64, i64) {
+            sample_times(start, end, step)
+                .map(|time| self.calculate(time))
+                .fold((i64::MAX, i64::MIN), |(low, high), value| {
+                    (low.min(value), high.max(value))
+                })
+        }
+
+        /// The smallest and largest value in each of `pixel_width` columns from `start` to `end`,
+        /// like an oscilloscope's envelope display, so fast signals don't alias in a plot
+        ///
+        /// Each column is sampled a fixed number of times however wide it is
+        fn for_display(&self, start: f64, end: f64, pixel_width: usize) -> Vec<(i64, i64)> {
+            const SAMPLES_PER_COLUMN: usize = 16;
+
+            let column_width = (end - start) / pixel_width as f64;
+            (0..pixel_width)
+                .map(|column| {
+                    let column_start = start + column as f64 * column_width;
+                    (0..SAMPLES_PER_COLUMN)
+                        .map(|i| {
+                            let offset = i as f64 / SAMPLES_PER_COLUMN as f64 * column_width;
+                            self.calculate(column_start + offset)
+                        })
+                        .fold((i64::MAX, i64::MIN), |(low, high), value| {
+                            (low.min(value), high.max(value))
+                        })
+                })
+                .collect()
+        }
+
+        /// The analog trace from `start` up to (but not including) `end` as SVG path data,
+        /// scaled to fill a `width` by `height` pixel box
+        ///
+        /// Time runs left to right and the value range of the samples bottom to top; a flat trace
+        /// is drawn across the middle of the box
+        fn to_svg_path(&self, start: f64, end: f64, step: f64, width: f64, height: f64) -> String {
+            let samples: Vec<(f64, f64)> = sample_times(start, end, step)
+                .map(|time| (time, self.calculate_float(time)))
+                .collect();
+
+            let low = samples.iter().map(|s| s.1).fold(f64::INFINITY, f64::min);
+            let high = samples
+                .iter()
+                .map(|s| s.1)
+                .fold(f64::NEG_INFINITY, f64::max);
+
+            samples
+                .iter()
+                .enumerate()
+                .map(|(i, &(time, value))| {
+                    let x = (time - start) / (end - start) * width;
+                    let y = if high > low {
+                        (high - value) / (high - low) * height
+                    } else {
+                        height / 2.0
+                    };
+                    let command = if i == 0 { 'M' } else { 'L' };
+                    format!("{command}{x:.2} {y:.2}")
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+
+        /// Write `duration` seconds of the analog trace as a mono 16-bit PCM WAV file, normalized so
+        /// the largest sample is full scale
+        #[cfg(feature = "wav")]
+        fn write_wav(
+            &self,
+            path: &std::path::Path,
+            duration: f64,
+            sample_rate: u32,
+        ) -> std::io::Result<()> {
+            let trace: Vec<f64> = sample_times(0.0, duration, 1.0 / sample_rate as f64)
+                .map(|time| self.calculate_float(time))
+                .collect();
+            let peak = trace
+                .iter()
+                .fold(0.0, |peak: f64, value| peak.max(value.abs()));
+            let gain = if peak > 0.0 {
+                i16::MAX as f64 / peak
+            } else {
+                0.0
             };
-            let value = value + self.noise() * self.amplitude;
-            let value = value.clamp(self.minimum, self.maximum);
+
+            let data_len = trace.len() as u32 * 2;
+            let mut wav = Vec::with_capacity(44 + data_len as usize);
+            wav.extend_from_slice(b"RIFF");
+            wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+            wav.extend_from_slice(b"WAVEfmt ");
+            wav.extend_from_slice(&16u32.to_le_bytes());
+            wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+            wav.extend_from_slice(&1u16.to_le_bytes()); // Mono
+            wav.extend_from_slice(&sample_rate.to_le_bytes());
+            wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+            wav.extend_from_slice(&2u16.to_le_bytes());
+            wav.extend_from_slice(&16u16.to_le_bytes());
+            wav.extend_from_slice(b"data");
+            wav.extend_from_slice(&data_len.to_le_bytes());
+            for value in trace {
+                wav.extend_from_slice(&((value * gain).round() as i16).to_le_bytes());
+            }
+
+            std::fs::write(path, wav)
+        }
+
+        /// Write the values at every `step` from `start` up to (but not including) `end` as a
+        /// one-dimensional int64 array in numpy's `.npy` format (version 1.0), for `numpy.load`
+        #[cfg(feature = "npy")]
+        fn write_npy(
+            &self,
+            path: &std::path::Path,
+            start: f64,
+            end: f64,
+            step: f64,
+        ) -> std::io::Result<()> {
+            let values: Vec<i64> = sample_times(start, end, step)
+                .map(|time| self.calculate(time))
+                .collect();
+
+            // The header is padded with spaces and a newline so the data starts 64-byte aligned
+            let mut header = format!(
+                "{{'descr': '<i8', 'fortran_order': False, 'shape': ({},), }}",
+                values.len()
+            );
+            let padding = 64 - (10 + header.len() + 1) % 64;
+            header.push_str(&" ".repeat(padding % 64));
+            header.push('\n');
+
+            let mut npy = Vec::with_capacity(10 + header.len() + values.len() * 8);
+            npy.extend_from_slice(b"\x93NUMPY");
+            npy.extend_from_slice(&[1, 0]);
+            npy.extend_from_slice(&(header.len() as u16).to_le_bytes());
+            npy.extend_from_slice(header.as_bytes());
+            for value in values {
+                npy.extend_from_slice(&value.to_le_bytes());
+            }
+
+            std::fs::write(path, npy)
+        }
+
+        /// Calculate the value of the signal at a given time like `calculate`, along with whether
+        /// it had to be clamped, either to the minimum and maximum or to the range of the bits
+        fn calculate_checked(&self, time: f64) -> (i64, bool) {
+            let value = self.calculate_float(time);
+            let limited = self.clamp_to_limits(value);
+
+            let count = (limited / self.scale_factor() - self.get_offset()).round();
+            let overflowed = count < self.min_count() as f64 || count > self.max_count() as f64;
+
+            (self.shrink_to_fit(limited), limited != value || overflowed)
+        }
+
+        /// Calculate the value of the signal at a given time with noise
+        ///
+        /// The analog value is clamped to the physical rails (`minimum` and `maximum`) before it is
+        /// stored in the signal's bits. Any waveform shaping, such as `WaveformBounds`, happens
+        /// first, in `calculate_float`.
+        fn calculate(&self, time: f64) -> i64 {
+            let value = self.calculate_float(time);
+            let value = self.clamp_to_limits(value);
             self.shrink_to_fit(value)
         }
     }
 
+    impl Debug for dyn Signal {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("Signal")
+                .field("type", &self.get_type_name())
+                .field("minimum", &self.get_minimum())
+                .field("maximum", &self.get_maximum())
+                .field("amplitude", &self.get_amplitude())
+                .field("period", &self.get_period())
+                .field("phase", &self.get_phase())
+                .field("num_bits", &self.get_num_bits())
+                .field("is_signed", &self.is_signed())
+                .field("scale", &self.get_scale())
+                .field("offset", &self.get_offset())
+                .finish()
+        }
+    }
+
+    impl Signal for Sine {
+        signal_type_getters!(Sine);
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            sine_value(self.amplitude, self.period, self.phase, time)
+        }
+    }
+
+    impl Signal for Square {
+        signal_type_getters!(Square);
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            square_value(self.amplitude, self.period, self.phase, time)
+        }
+    }
+
+    impl Signal for Triangle {
+        signal_type_getters!(Triangle);
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            triangle_value(self.amplitude, self.period, self.phase, time)
+        }
+    }
+
     impl Signal for Sawtooth {
         signal_type_getters!(Sawtooth);
 
-        fn calculate(&self, time: f64) -> i64 {
-            let t: f64 = (time + self.phase) % self.period / self.period;
-            let value = self.amplitude * (t * 2.0 - 1.0);
-            let value = value + self.noise() * self.amplitude;
-            let value = value.clamp(self.minimum, self.maximum);
-            self.shrink_to_fit(value)
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            sawtooth_value(self.amplitude, self.period, self.phase, time)
         }
     }
 
     impl Signal for Constant {
         signal_type_getters!(Constant);
 
-        fn calculate(&self, _time: f64) -> i64 {
-            let value = self.amplitude;
-            let value = value + self.noise() * self.amplitude;
-            let value = value.clamp(self.minimum, self.maximum);
-            self.shrink_to_fit(value)
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            constant_value(self.amplitude, self.period, self.phase, time)
         }
     }
-}
 
-#[cfg(test)]
-mod signal_test {
-    use crate::{
-        signal_generator::{get_max_limit, get_min_limit},
-        signal_type::generators::Signal,
-    };
+    /// A noise-free triangle that sweeps every raw count from the smallest to the largest and back
+    /// each period, to use as a golden reference when calibrating
+    #[derive(Debug)]
+    pub struct TestPattern {
+        pub period: f64,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+    }
 
-    #[test]
-    fn sine_test() {
-        use super::*;
+    impl TestPattern {
+        /// The raw count at a given time, rising from `min_count` at the start of each period
+        /// to `max_count` halfway through
+        pub fn count_at(&self, time: f64) -> i64 {
+            let t = cycle_fraction(time, 0.0, self.period);
+            let fraction = 1.0 - (2.0 * t - 1.0).abs();
+            let span = self.max_count() as f64 - self.min_count() as f64;
+            self.min_count() + (fraction * span).round() as i64
+        }
+    }
 
-        let signal_type = SignalType::Constant;
-        let minimum = get_min_limit();
-        let maximum = get_max_limit();
-        let amplitude = 8200.0;
-        let period = 100.0;
-        let phase = 0.0;
-        let num_bits: u8 = 16;
-        let is_signed = true;
-        let scale = 100.0;
-        let offset = 0.0;
+    impl Signal for TestPattern {
+        fn get_type(&self) -> SignalType {
+            SignalType::Triangle
+        }
+        fn get_minimum(&self) -> f64 {
+            self.count_to_value(self.min_count())
+                .min(self.count_to_value(self.max_count()))
+        }
+        fn get_maximum(&self) -> f64 {
+            self.count_to_value(self.min_count())
+                .max(self.count_to_value(self.max_count()))
+        }
+        fn get_amplitude(&self) -> f64 {
+            (self.get_maximum() - self.get_minimum()) / 2.0
+        }
+        fn get_period(&self) -> f64 {
+            self.period
+        }
+        fn get_phase(&self) -> f64 {
+            0.0
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.num_bits
+        }
+        fn is_signed(&self) -> bool {
+            self.is_signed
+        }
+        fn get_scale(&self) -> f64 {
+            self.scale
+        }
+        fn get_offset(&self) -> f64 {
+            self.offset
+        }
 
-        let signal = generators::Constant {
-            minimum,
-            maximum,
-            amplitude,
-            period,
-            phase,
-            num_bits,
-            is_signed,
-            scale,
-            offset,
-        };
+        /// The triangle spends as long at each level as at its mirror image, so averages to the
+        /// middle of the range
+        fn period_mean(&self) -> f64 {
+            (self.get_minimum() + self.get_maximum()) / 2.0
+        }
 
-        dbg!(signal.calculate(0.0));
+        /// The full range is crossed in each half period
+        fn max_slew_rate(&self) -> f64 {
+            if self.period <= 0.0 {
+                return 0.0;
+            }
+            2.0 * (self.get_maximum() - self.get_minimum()) / self.period
+        }
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            self.count_to_value(self.count_at(time))
+        }
+
+        fn noise(&self) -> f64 {
+            0.0
+        }
+
+        fn noise_variance(&self) -> f64 {
+            0.0
+        }
+    }
+
+    /// A noise-free staircase that steps through every raw count from the smallest to the largest,
+    /// holding each for `samples_per_code` samples, then starts over, for ADC and DAC linearity
+    /// testing
+    ///
+    /// Which sample a time falls on depends on the spacing of the samples, so the sweep has to be
+    /// told the `sample_step` it will be calculated at
+    #[derive(Debug)]
+    pub struct CodeSweep {
+        /// The time between the samples it will be calculated at, in seconds
+        pub sample_step: f64,
+        pub samples_per_code: u32,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+    }
+
+    impl CodeSweep {
+        /// The number of codes in one sweep, `2^num_bits`
+        ///
+        /// An i128, as 64 signed or 63 unsigned bits have one more code than an i64 can count
+        fn num_codes(&self) -> i128 {
+            self.max_count() as i128 - self.min_count() as i128 + 1
+        }
+
+        /// The raw count at a given time, `min_count` for the first `samples_per_code` samples
+        /// from 0, then one more for each `samples_per_code` after
+        pub fn count_at(&self, time: f64) -> i64 {
+            if !(self.sample_step > 0.0 && self.sample_step.is_finite()) {
+                return self.min_count();
+            }
+            let sample = (time / self.sample_step).round() as i64;
+            let code = sample.div_euclid(self.samples_per_code.max(1) as i64);
+            // Always below the number of codes, so the sum is at most `max_count`
+            (self.min_count() as i128 + (code as i128).rem_euclid(self.num_codes())) as i64
+        }
+    }
+
+    impl Signal for CodeSweep {
+        fn get_type(&self) -> SignalType {
+            SignalType::Sawtooth
+        }
+        fn get_minimum(&self) -> f64 {
+            self.count_to_value(self.min_count())
+                .min(self.count_to_value(self.max_count()))
+        }
+        fn get_maximum(&self) -> f64 {
+            self.count_to_value(self.min_count())
+                .max(self.count_to_value(self.max_count()))
+        }
+        fn get_amplitude(&self) -> f64 {
+            (self.get_maximum() - self.get_minimum()) / 2.0
+        }
+        /// The time to step through every code once
+        fn get_period(&self) -> f64 {
+            self.sample_step * self.samples_per_code.max(1) as f64 * self.num_codes() as f64
+        }
+        fn get_phase(&self) -> f64 {
+            0.0
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.num_bits
+        }
+        fn is_signed(&self) -> bool {
+            self.is_signed
+        }
+        fn get_scale(&self) -> f64 {
+            self.scale
+        }
+        fn get_offset(&self) -> f64 {
+            self.offset
+        }
+
+        /// Every code is held for as long as the others, so the sweep averages to the middle of
+        /// the range
+        fn period_mean(&self) -> f64 {
+            (self.get_minimum() + self.get_maximum()) / 2.0
+        }
+
+        /// The sweep only ever steps, so is flat between the steps
+        fn max_slew_rate(&self) -> f64 {
+            0.0
+        }
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            self.count_to_value(self.count_at(time))
+        }
+
+        fn noise(&self) -> f64 {
+            0.0
+        }
+
+        fn noise_variance(&self) -> f64 {
+            0.0
+        }
+    }
+
+    /// An impulse train (Dirac comb) for system identification: `amplitude` at the one sample
+    /// nearest each period boundary, and `minimum` at every other sample
+    ///
+    /// Which sample is nearest depends on the spacing of the samples, so the train has to be
+    /// told the `sample_step` it will be calculated at
+    #[derive(Debug)]
+    pub struct ImpulseTrain {
+        pub minimum: f64,
+        pub maximum: f64,
+        pub amplitude: f64,
+        pub period: f64,
+        pub phase: f64,
+        /// The time between the samples it will be calculated at, in seconds
+        pub sample_step: f64,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+    }
+
+    impl ImpulseTrain {
+        /// Whether the sample at `time` is the one nearest a period boundary
+        pub fn is_impulse(&self, time: f64) -> bool {
+            // Shift by half a step so the boundary's sample is the first in its period
+            let shifted = cycle_fraction(time + self.sample_step / 2.0, self.phase, self.period);
+            shifted * self.period < self.sample_step
+        }
+    }
+
+    impl Signal for ImpulseTrain {
+        fn get_type(&self) -> SignalType {
+            SignalType::Square
+        }
+        fn get_minimum(&self) -> f64 {
+            self.minimum
+        }
+        fn get_maximum(&self) -> f64 {
+            self.maximum
+        }
+        fn get_amplitude(&self) -> f64 {
+            self.amplitude
+        }
+        fn get_period(&self) -> f64 {
+            self.period
+        }
+        fn get_phase(&self) -> f64 {
+            self.phase
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.num_bits
+        }
+        fn is_signed(&self) -> bool {
+            self.is_signed
+        }
+        fn get_scale(&self) -> f64 {
+            self.scale
+        }
+        fn get_offset(&self) -> f64 {
+            self.offset
+        }
+
+        fn effective_peak(&self) -> f64 {
+            self.amplitude.abs().max(self.minimum.abs())
+        }
+
+        /// One sample in each period is at the amplitude and the rest are at the minimum
+        fn period_mean(&self) -> f64 {
+            let duty = if self.period > 0.0 {
+                (self.sample_step / self.period).clamp(0.0, 1.0)
+            } else {
+                // With no cycle to step through, every sample is an impulse
+                1.0
+            };
+            self.minimum + (self.amplitude - self.minimum) * duty
+        }
+
+        /// The impulses are single samples, so it is flat between them
+        fn max_slew_rate(&self) -> f64 {
+            0.0
+        }
+
+        /// Enough bits for the jump from the minimum to the amplitude
+        fn recommended_bits(&self, headroom_fraction: f64) -> u8 {
+            let range = (self.amplitude - self.minimum).abs();
+            bits_for_resolution(range * (1.0 + headroom_fraction), self.verify_lsb())
+        }
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            if self.is_impulse(time) {
+                self.amplitude
+            } else {
+                self.minimum
+            }
+        }
+    }
+
+    /// A single linear ramp from `minimum` to `maximum` over `ramp_duration` seconds starting at
+    /// time 0, holding at `minimum` before and at `maximum` after
+    #[derive(Debug)]
+    pub struct Ramp {
+        pub minimum: f64,
+        pub maximum: f64,
+        /// How long the ramp takes, in seconds
+        pub ramp_duration: f64,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+    }
+
+    impl Signal for Ramp {
+        fn get_type(&self) -> SignalType {
+            SignalType::Sawtooth
+        }
+        fn get_minimum(&self) -> f64 {
+            self.minimum
+        }
+        fn get_maximum(&self) -> f64 {
+            self.maximum
+        }
+        /// Half the height of the ramp
+        fn get_amplitude(&self) -> f64 {
+            (self.maximum - self.minimum) / 2.0
+        }
+        /// The ramp duration
+        fn get_period(&self) -> f64 {
+            self.ramp_duration
+        }
+        fn get_phase(&self) -> f64 {
+            0.0
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.num_bits
+        }
+        fn is_signed(&self) -> bool {
+            self.is_signed
+        }
+        fn get_scale(&self) -> f64 {
+            self.scale
+        }
+        fn get_offset(&self) -> f64 {
+            self.offset
+        }
+
+        fn max_slew_rate(&self) -> f64 {
+            ((self.maximum - self.minimum) / self.ramp_duration).abs()
+        }
+
+        fn effective_peak(&self) -> f64 {
+            self.minimum.abs().max(self.maximum.abs())
+        }
+
+        /// The ramp only runs once, so has no period to average over
+        fn period_mean(&self) -> f64 {
+            f64::NAN
+        }
+
+        /// The ramp only runs once, so never starts another cycle
+        fn time_to_next_period(&self, _time: f64) -> f64 {
+            f64::INFINITY
+        }
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            // A zero duration steps straight to the maximum
+            let progress = match self.ramp_duration > 0.0 {
+                true => (time / self.ramp_duration).clamp(0.0, 1.0),
+                false => (time >= 0.0) as u8 as f64,
+            };
+            self.minimum + (self.maximum - self.minimum) * progress
+        }
+    }
+
+    /// Two sines at close frequencies summed together, for intermodulation distortion testing
+    #[derive(Debug)]
+    pub struct TwoTone {
+        pub minimum: f64,
+        pub maximum: f64,
+        /// The frequency of the first tone in Hz
+        pub freq1: f64,
+        /// The frequency of the second tone in Hz
+        pub freq2: f64,
+        pub amplitude1: f64,
+        pub amplitude2: f64,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+    }
+
+    impl Signal for TwoTone {
+        fn get_type(&self) -> SignalType {
+            SignalType::Sine
+        }
+        fn get_minimum(&self) -> f64 {
+            self.minimum
+        }
+        fn get_maximum(&self) -> f64 {
+            self.maximum
+        }
+        /// The largest the two tones can reach together
+        fn get_amplitude(&self) -> f64 {
+            self.amplitude1.abs() + self.amplitude2.abs()
+        }
+        /// The period of the first tone
+        fn get_period(&self) -> f64 {
+            1.0 / self.freq1
+        }
+        fn get_phase(&self) -> f64 {
+            0.0
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.num_bits
+        }
+        fn is_signed(&self) -> bool {
+            self.is_signed
+        }
+        fn get_scale(&self) -> f64 {
+            self.scale
+        }
+        fn get_offset(&self) -> f64 {
+            self.offset
+        }
+
+        fn period_mean(&self) -> f64 {
+            0.0
+        }
+
+        fn max_slew_rate(&self) -> f64 {
+            2.0 * PI * (self.amplitude1 * self.freq1).abs()
+                + 2.0 * PI * (self.amplitude2 * self.freq2).abs()
+        }
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            self.amplitude1 * (2.0 * PI * self.freq1 * time).sin()
+                + self.amplitude2 * (2.0 * PI * self.freq2 * time).sin()
+        }
+    }
+
+    /// A fundamental sine plus `num_harmonics` harmonics at integer multiples of its frequency,
+    /// each quieter than the last by `rolloff_db_per_octave`, for realistic periodic signals
+    ///
+    /// The `k`th multiple of the fundamental is `log2(k)` octaves above it, so has an amplitude of
+    /// `amplitude * 10^(-rolloff_db_per_octave * log2(k) / 20)`
+    #[derive(Debug)]
+    pub struct HarmonicSeries {
+        pub minimum: f64,
+        pub maximum: f64,
+        /// The amplitude of the fundamental
+        pub amplitude: f64,
+        /// The period of the fundamental
+        pub period: f64,
+        pub phase: f64,
+        /// The number of harmonics on top of the fundamental
+        pub num_harmonics: u32,
+        pub rolloff_db_per_octave: f64,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+    }
+
+    impl HarmonicSeries {
+        /// The amplitude of the `multiple`th multiple of the fundamental, where 1 is the
+        /// fundamental itself
+        pub fn harmonic_amplitude(&self, multiple: u32) -> f64 {
+            let octaves = (multiple as f64).log2();
+            self.amplitude * 10f64.powf(-self.rolloff_db_per_octave * octaves / 20.0)
+        }
+
+        /// The multiples of the fundamental that are summed, with their amplitudes
+        fn harmonics(&self) -> impl Iterator<Item = (u32, f64)> + '_ {
+            (1..=self.num_harmonics.saturating_add(1))
+                .map(|multiple| (multiple, self.harmonic_amplitude(multiple)))
+        }
+    }
+
+    impl Signal for HarmonicSeries {
+        fn get_type(&self) -> SignalType {
+            SignalType::Sine
+        }
+        fn get_minimum(&self) -> f64 {
+            self.minimum
+        }
+        fn get_maximum(&self) -> f64 {
+            self.maximum
+        }
+        /// The largest the harmonics can reach together
+        fn get_amplitude(&self) -> f64 {
+            self.harmonics().map(|(_, amplitude)| amplitude.abs()).sum()
+        }
+        fn get_period(&self) -> f64 {
+            self.period
+        }
+        fn get_phase(&self) -> f64 {
+            self.phase
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.num_bits
+        }
+        fn is_signed(&self) -> bool {
+            self.is_signed
+        }
+        fn get_scale(&self) -> f64 {
+            self.scale
+        }
+        fn get_offset(&self) -> f64 {
+            self.offset
+        }
+
+        fn period_mean(&self) -> f64 {
+            0.0
+        }
+
+        fn max_slew_rate(&self) -> f64 {
+            self.harmonics()
+                .map(|(multiple, amplitude)| {
+                    2.0 * PI * (amplitude * multiple as f64 / self.period).abs()
+                })
+                .sum()
+        }
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            self.harmonics()
+                .map(|(multiple, amplitude)| {
+                    sine_value(amplitude, self.period / multiple as f64, self.phase, time)
+                })
+                .sum()
+        }
+    }
+
+    /// A sine that hops through `frequencies` in order, spending `hop_period` seconds on each and
+    /// starting over after the last, for spread-spectrum emulation
+    ///
+    /// The phase carries on across each hop, so the waveform never jumps
+    #[derive(Debug)]
+    pub struct FrequencyHop {
+        pub minimum: f64,
+        pub maximum: f64,
+        pub amplitude: f64,
+        /// The frequencies to hop between, in Hz
+        pub frequencies: Vec<f64>,
+        /// The time spent on each frequency, in seconds
+        pub hop_period: f64,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+    }
+
+    impl FrequencyHop {
+        /// The frequency being transmitted at a given time, or `None` with nothing to hop between
+        pub fn frequency_at(&self, time: f64) -> Option<f64> {
+            let hops = (time / self.hop_period).floor();
+            let index = (hops as i64).rem_euclid(self.frequencies.len().max(1) as i64);
+            self.frequencies.get(index as usize).copied()
+        }
+    }
+
+    impl Signal for FrequencyHop {
+        fn get_type(&self) -> SignalType {
+            SignalType::Sine
+        }
+        fn get_minimum(&self) -> f64 {
+            self.minimum
+        }
+        fn get_maximum(&self) -> f64 {
+            self.maximum
+        }
+        fn get_amplitude(&self) -> f64 {
+            self.amplitude
+        }
+        /// The time spent on each frequency
+        fn get_period(&self) -> f64 {
+            self.hop_period
+        }
+        fn get_phase(&self) -> f64 {
+            0.0
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.num_bits
+        }
+        fn is_signed(&self) -> bool {
+            self.is_signed
+        }
+        fn get_scale(&self) -> f64 {
+            self.scale
+        }
+        fn get_offset(&self) -> f64 {
+            self.offset
+        }
+
+        /// Every hop is a sine, which averages to zero
+        fn period_mean(&self) -> f64 {
+            0.0
+        }
+
+        fn max_slew_rate(&self) -> f64 {
+            let fastest = self
+                .frequencies
+                .iter()
+                .fold(0.0, |fastest: f64, f| fastest.max(f.abs()));
+            2.0 * PI * self.amplitude.abs() * fastest
+        }
+
+        /// The hops don't line up with the cycles, so there is no period boundary to count down to
+        fn time_to_next_period(&self, _time: f64) -> f64 {
+            f64::INFINITY
+        }
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            if self.frequencies.is_empty() || self.hop_period.is_nan() || self.hop_period <= 0.0 {
+                return 0.0;
+            }
+
+            // The cycles completed in every full pass through the frequencies, then in the hops
+            // of this pass, then so far in this hop
+            let hops = (time / self.hop_period).floor();
+            let count = self.frequencies.len() as f64;
+            let passes = (hops / count).floor();
+            let index = (hops - passes * count) as usize;
+            let per_pass: f64 = self.frequencies.iter().sum();
+            let this_pass: f64 = self.frequencies[..index].iter().sum();
+            let cycles = (passes * per_pass + this_pass) * self.hop_period
+                + self.frequencies[index] * (time - hops * self.hop_period);
+
+            self.amplitude * (2.0 * PI * cycles.fract()).sin()
+        }
+    }
+
+    /// A signal whose value comes from a user-provided function of time, e.g. the state of a
+    /// simulated plant in a closed-loop test
+    ///
+    /// The function's output goes through the same clamping and quantization as any other signal.
+    /// It has no waveform of its own, so it reports itself as a constant and adds no noise.
+    pub struct Callback {
+        pub callback: RefCell<Box<dyn FnMut(f64) -> f64 + Send>>,
+        pub minimum: f64,
+        pub maximum: f64,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+    }
+
+    impl Callback {
+        pub fn new(
+            callback: impl FnMut(f64) -> f64 + Send + 'static,
+            minimum: f64,
+            maximum: f64,
+            num_bits: u8,
+            is_signed: bool,
+            scale: f64,
+            offset: f64,
+        ) -> Self {
+            Callback {
+                callback: RefCell::new(Box::new(callback)),
+                minimum,
+                maximum,
+                num_bits,
+                is_signed,
+                scale,
+                offset,
+            }
+        }
+    }
+
+    impl Signal for Callback {
+        fn get_type(&self) -> SignalType {
+            SignalType::Constant
+        }
+        fn get_minimum(&self) -> f64 {
+            self.minimum
+        }
+        fn get_maximum(&self) -> f64 {
+            self.maximum
+        }
+        fn get_amplitude(&self) -> f64 {
+            0.0
+        }
+        fn get_period(&self) -> f64 {
+            0.0
+        }
+        fn get_phase(&self) -> f64 {
+            0.0
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.num_bits
+        }
+        fn is_signed(&self) -> bool {
+            self.is_signed
+        }
+        fn get_scale(&self) -> f64 {
+            self.scale
+        }
+        fn get_offset(&self) -> f64 {
+            self.offset
+        }
+
+        /// The callback could return anything, so only the limits bound it
+        fn effective_peak(&self) -> f64 {
+            self.minimum.abs().max(self.maximum.abs())
+        }
+
+        /// Nothing is known about the callback's mean until it is calculated
+        fn period_mean(&self) -> f64 {
+            f64::NAN
+        }
+
+        /// Enough bits for the whole range between the limits
+        fn recommended_bits(&self, headroom_fraction: f64) -> u8 {
+            let range = (self.maximum - self.minimum).abs();
+            bits_for_resolution(range * (1.0 + headroom_fraction), self.verify_lsb())
+        }
+
+        /// The limits stand in for the amplitude, so only warns if half the range between them is
+        /// under one LSB
+        fn validate(&self) -> Vec<SignalWarning> {
+            let mut warnings = Vec::new();
+
+            let amplitude = (self.maximum - self.minimum).abs() / 2.0;
+            let lsb = self.verify_lsb().abs();
+            if amplitude < lsb {
+                warnings.push(SignalWarning::SubLsbAmplitude { amplitude, lsb });
+            }
+
+            warnings
+        }
+
+        fn calculate_noiseless(&self, time: f64) -> f64 {
+            (self.callback.borrow_mut())(time)
+        }
+
+        fn noise(&self) -> f64 {
+            0.0
+        }
+
+        fn noise_variance(&self) -> f64 {
+            0.0
+        }
+    }
+
+    /// One second-order section of a Butterworth filter, in transposed direct form II
+    #[derive(Copy, Clone, Debug)]
+    struct Biquad {
+        b: [f64; 3],
+        a: [f64; 2],
+        z: [f64; 2],
+    }
+
+    impl Biquad {
+        /// A low-pass or high-pass section with a cutoff of `cutoff` Hz and a quality of `q`,
+        /// sampled at `sample_rate`
+        fn new(cutoff: f64, q: f64, sample_rate: f64, high_pass: bool) -> Self {
+            let (sin, cos) = (2.0 * PI * cutoff / sample_rate).sin_cos();
+            let alpha = sin / (2.0 * q);
+            let a0 = 1.0 + alpha;
+            let (b0, b1) = match high_pass {
+                true => ((1.0 + cos) / 2.0, -(1.0 + cos)),
+                false => ((1.0 - cos) / 2.0, 1.0 - cos),
+            };
+            Biquad {
+                b: [b0 / a0, b1 / a0, b0 / a0],
+                a: [-2.0 * cos / a0, (1.0 - alpha) / a0],
+                z: [0.0; 2],
+            }
+        }
+
+        fn process(&mut self, input: f64) -> f64 {
+            let output = self.b[0] * input + self.z[0];
+            self.z[0] = self.b[1] * input - self.a[0] * output + self.z[1];
+            self.z[1] = self.b[2] * input - self.a[1] * output;
+            output
+        }
+    }
+
+    /// The generator and filter sections of a `BandNoise`
+    type BandNoiseState = (StdRng, Vec<Biquad>);
+
+    /// Seeded Gaussian white noise band-pass filtered to `f_low` to `f_high` Hz, for testing
+    /// band-specific filters
+    ///
+    /// The filter is a 4th order Butterworth high-pass at `f_low` followed by a 4th order
+    /// Butterworth low-pass at `f_high`, so it rolls off at 24 dB per octave either side of the
+    /// band. A cutoff at or below 0, or at or above Nyquist, leaves that side unfiltered. The white
+    /// noise is scaled so the band carries an RMS of about `amplitude`.
+    ///
+    /// The signal is all noise, so its noiseless value is the mean of 0 and only `calculate_float`
+    /// returns the filtered noise. Each of those calculations moves on by one sample, so the
+    /// signal should be calculated at increasing times `sample_step` apart. The noise is drawn from
+    /// a generator seeded with `seed`, so the same sequence of calculations always gives the same
+    /// samples. Like the built-in noise, it is disabled while deterministic mode is enabled.
+    #[derive(Debug)]
+    pub struct BandNoise {
+        pub minimum: f64,
+        pub maximum: f64,
+        /// The approximate RMS of the filtered noise
+        pub amplitude: f64,
+        /// The lower edge of the band in Hz
+        pub f_low: f64,
+        /// The upper edge of the band in Hz
+        pub f_high: f64,
+        /// The time between the samples it will be calculated at, in seconds
+        pub sample_step: f64,
+        pub seed: u64,
+        pub num_bits: u8,
+        pub is_signed: bool,
+        pub scale: f64,
+        pub offset: f64,
+        /// Built from the configuration at the first calculation
+        state: RefCell<Option<BandNoiseState>>,
+    }
+
+    impl BandNoise {
+        /// Band-limited noise with the minimum, maximum, amplitude, bits, scale and offset of
+        /// `params`; its period and phase are unused
+        pub fn new(
+            params: &SignalParams,
+            f_low: f64,
+            f_high: f64,
+            sample_step: f64,
+            seed: u64,
+        ) -> Self {
+            BandNoise {
+                minimum: params.minimum,
+                maximum: params.maximum,
+                amplitude: params.amplitude,
+                f_low,
+                f_high,
+                sample_step,
+                seed,
+                num_bits: params.num_bits,
+                is_signed: params.is_signed,
+                scale: params.scale,
+                offset: params.offset,
+                state: RefCell::new(None),
+            }
+        }
+
+        /// The band the noise is confined to, limited to 0 to Nyquist, as (low, high) in Hz
+        fn band(&self) -> (f64, f64) {
+            let nyquist = 0.5 / self.sample_step;
+            (self.f_low.max(0.0), self.f_high.min(nyquist))
+        }
+
+        /// A fresh generator and filter for the current configuration
+        fn initial_state(&self) -> BandNoiseState {
+            let sample_rate = 1.0 / self.sample_step;
+            let nyquist = sample_rate / 2.0;
+            // The quality of each section of a 4th order Butterworth filter
+            let qs = [0.541_196_100_146_197, 1.306_562_964_876_376_7];
+
+            let mut sections = Vec::new();
+            if self.f_low > 0.0 && self.f_low < nyquist {
+                sections.extend(qs.map(|q| Biquad::new(self.f_low, q, sample_rate, true)));
+            }
+            if self.f_high > 0.0 && self.f_high < nyquist {
+                sections.extend(qs.map(|q| Biquad::new(self.f_high, q, sample_rate, false)));
+            }
+            (StdRng::seed_from_u64(self.seed), sections)
+        }
+
+        /// Filter the next white noise sample, advancing the state
+        fn step(&self) -> f64 {
+            let mut state = self.state.borrow_mut();
+            let (rng, sections) = state.get_or_insert_with(|| self.initial_state());
+
+            // Spread the white noise so the part inside the band has an RMS of `amplitude`
+            let nyquist = 0.5 / self.sample_step;
+            let (low, high) = self.band();
+            let band = high - low;
+            let deviation = match band > 0.0 {
+                true => self.amplitude * (nyquist / band).sqrt(),
+                false => 0.0,
+            };
+
+            // Box-Muller transform
+            let u1: f64 = 1.0 - rng.gen::<f64>();
+            let u2: f64 = rng.gen();
+            let white = deviation * (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+
+            sections
+                .iter_mut()
+                .fold(white, |sample, section| section.process(sample))
+        }
+    }
+
+    impl Signal for BandNoise {
+        fn get_type(&self) -> SignalType {
+            SignalType::Constant
+        }
+        fn get_minimum(&self) -> f64 {
+            self.minimum
+        }
+        fn get_maximum(&self) -> f64 {
+            self.maximum
+        }
+        fn get_amplitude(&self) -> f64 {
+            self.amplitude
+        }
+        fn get_period(&self) -> f64 {
+            0.0
+        }
+        fn get_phase(&self) -> f64 {
+            0.0
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.num_bits
+        }
+        fn is_signed(&self) -> bool {
+            self.is_signed
+        }
+        fn get_scale(&self) -> f64 {
+            self.scale
+        }
+        fn get_offset(&self) -> f64 {
+            self.offset
+        }
+
+        /// Gaussian noise passes 4 standard deviations a few times in every 100,000 samples
+        fn effective_peak(&self) -> f64 {
+            4.0 * self.amplitude.abs()
+        }
+
+        fn period_mean(&self) -> f64 {
+            0.0
+        }
+
+        /// Enough bits for the noise to swing to its effective peak either way
+        fn recommended_bits(&self, headroom_fraction: f64) -> u8 {
+            let range = 2.0 * self.effective_peak();
+            bits_for_resolution(range * (1.0 + headroom_fraction), self.verify_lsb())
+        }
+
+        /// The RMS slope of noise spread evenly over the band, `2π * amplitude * sqrt(mean f²)`,
+        /// times the same 4 as `effective_peak`
+        fn max_slew_rate(&self) -> f64 {
+            let (low, high) = self.band();
+            if high.is_nan() || low.is_nan() || high <= low {
+                return 0.0;
+            }
+            let mean_square_frequency = (high.powi(3) - low.powi(3)) / (3.0 * (high - low));
+            4.0 * 2.0 * PI * self.amplitude.abs() * mean_square_frequency.sqrt()
+        }
+
+        fn calculate_noiseless(&self, _time: f64) -> f64 {
+            0.0
+        }
+
+        fn calculate_float(&self, _time: f64) -> f64 {
+            if is_deterministic() {
+                return 0.0;
+            }
+            self.step()
+        }
+
+        fn noise(&self) -> f64 {
+            0.0
+        }
+
+        /// The band carries an RMS of about `amplitude`
+        fn noise_variance(&self) -> f64 {
+            if is_deterministic() {
+                return 0.0;
+            }
+            self.amplitude * self.amplitude
+        }
+    }
+}
+
+#[cfg(test)]
+mod signal_test {
+    use crate::{
+        signal_generator::{get_max_limit, get_min_limit},
+        signal_type::generators::Signal,
+    };
+
+    /// A Callback of `waveform` plus Gaussian noise with a standard deviation of `deviation`,
+    /// drawn from the noise RNG, for tests that need more noise than the built-in level
+    ///
+    /// The noise is part of the callback, so `calculate_noiseless` and `calculate_float` each
+    /// draw their own
+    fn with_gaussian_noise(
+        waveform: impl Fn(f64) -> f64 + Send + 'static,
+        deviation: f64,
+        num_bits: u8,
+    ) -> super::generators::Callback {
+        use rand::Rng;
+        use std::f64::consts::PI;
+
+        let noisy = move |time| {
+            let noise = super::generators::with_rng(|rng| {
+                // Box-Muller transform
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+            });
+            waveform(time) + deviation * noise
+        };
+        super::generators::Callback::new(
+            noisy,
+            get_min_limit(),
+            get_max_limit(),
+            num_bits,
+            true,
+            1.0,
+            0.0,
+        )
+    }
+
+    #[test]
+    #[allow(unused_variables)]
+    fn sine_test() {
+        use super::*;
+
+        let signal_type = SignalType::Constant;
+        let minimum = get_min_limit();
+        let maximum = get_max_limit();
+        let amplitude = 8200.0;
+        let period = 100.0;
+        let phase = 0.0;
+        let num_bits: u8 = 16;
+        let is_signed = true;
+        let scale = 100.0;
+        let offset = 0.0;
+
+        let signal = generators::Constant {
+            minimum,
+            maximum,
+            amplitude,
+            period,
+            phase,
+            num_bits,
+            is_signed,
+            scale,
+            offset,
+        };
+
+        dbg!(signal.calculate(0.0));
+    }
+
+    #[test]
+    fn full_scale_test() {
+        use super::*;
+
+        let signal = generators::Sine {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 100.0,
+            period: 1.0,
+            phase: 0.0,
+            num_bits: 12,
+            is_signed: false,
+            scale: 0.1,
+            offset: -100.0,
+        };
+
+        // (4095 - 100) * 0.1 and (0 - 100) * 0.1
+        assert!((signal.full_scale() - 399.5).abs() < 1e-9);
+        assert!((signal.zero_scale() - -10.0).abs() < 1e-9);
+
+        let signal = generators::Sine {
+            is_signed: true,
+            offset: 0.0,
+            scale: 0.5,
+            ..signal
+        };
+
+        // 2047 * 0.5 and -2048 * 0.5
+        assert!((signal.full_scale() - 1023.5).abs() < 1e-9);
+        assert!((signal.zero_scale() - -1024.0).abs() < 1e-9);
+
+        // Scales of 1 or more are applied as 1, so the full scale is the largest value produced
+        let signal = generators::Sine {
+            amplitude: 5000.0,
+            offset: 10.0,
+            scale: 4.0,
+            ..signal
+        };
+        assert_eq!(signal.full_scale(), 2057.0);
+        assert_eq!(signal.zero_scale(), -2038.0);
+        assert_eq!(signal.calculate_pair(0.25).0, signal.full_scale());
+        assert_eq!(signal.calculate_pair(0.75).0, signal.zero_scale());
+    }
+
+    #[test]
+    fn noise_variance_test() {
+        use super::*;
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let signal = generators::test_constant(1000.0);
+
+        let count = 100_000;
+        let samples: Vec<f64> = (0..count).map(|_| signal.calculate_float(0.0)).collect();
+        let mean = samples.iter().sum::<f64>() / count as f64;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / count as f64;
+
+        let expected = signal.noise_variance();
+        assert!((expected - 100.0 / 3.0).abs() < 1e-9);
+        assert!((variance - expected).abs() / expected < 0.05);
+    }
+
+    #[test]
+    fn on_clock_test() {
+        use super::*;
+
+        let slow = generators::Sawtooth {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            period: 10.0,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        let fast = generators::test_sine(1000.0, 1.0);
+
+        // 20 Hz master clock over 2 seconds
+        let clock: Vec<f64> = (0..40).map(|i| i as f64 * 0.05).collect();
+
+        // 1 Hz channel holds each sample for 20 ticks
+        let slow_values = slow.on_clock(1.0, &clock);
+        assert_eq!(slow_values.len(), clock.len());
+        for chunk in slow_values.chunks(20) {
+            assert!(chunk.iter().all(|&value| value == chunk[0]));
+        }
+
+        // 10 Hz channel holds each sample for 2 ticks
+        let fast_values = fast.on_clock(0.1, &clock);
+        assert_eq!(fast_values.len(), clock.len());
+        for chunk in fast_values.chunks(2) {
+            assert_eq!(chunk[0], chunk[1]);
+        }
+    }
+
+    #[test]
+    fn calculate_range_pairs_test() {
+        use super::*;
+
+        let signal = generators::Triangle {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 50.0,
+            period: 2.0,
+            phase: 0.0,
+            num_bits: 12,
+            is_signed: true,
+            scale: 0.1,
+            offset: 5.0,
+        };
+
+        let (values, counts) = signal.calculate_range_pairs(0.0, 4.0, 0.05);
+        assert_eq!(values.len(), 80);
+        assert_eq!(counts.len(), 80);
+        for (value, count) in values.iter().zip(counts.iter()) {
+            assert!((value - (*count as f64 + 5.0) * 0.1).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn calculate_range_clean_and_noisy_test() {
+        use super::*;
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        // Callbacks have no noise of their own, so both traces match
+        let ramp =
+            generators::Callback::new(|time| time * 100.0, -1000.0, 1000.0, 16, true, 1.0, 0.0);
+        let (clean, noisy) = ramp.calculate_range_clean_and_noisy(0.0, 5.0, 0.01, 7);
+        assert_eq!(clean.len(), 500);
+        assert_eq!(clean, noisy);
+
+        let sine = || generators::test_sine(1000.0, 1.0);
+        let (clean, noisy) = sine().calculate_range_clean_and_noisy(0.0, 5.0, 0.01, 7);
+        assert_eq!(clean.len(), noisy.len());
+        assert_ne!(clean, noisy);
+        assert!(clean.iter().zip(&noisy).all(|(a, b)| (a - b).abs() <= 11));
+
+        // The same seed gives the same noise
+        assert_eq!(
+            sine().calculate_range_clean_and_noisy(0.0, 5.0, 0.01, 7),
+            (clean, noisy)
+        );
+    }
+
+    #[test]
+    fn calculate_frame_test() {
+        use super::*;
+        use crate::can_frame::ByteOrder;
+
+        // A zero amplitude constant has no noise, so its raw count is exactly -offset
+        let signal = generators::Constant {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 0.0,
+            period: 0.0,
+            phase: 0.0,
+            num_bits: 12,
+            is_signed: false,
+            scale: 1.0,
+            offset: -291.0,
+        };
+
+        assert_eq!(
+            signal.calculate_frame(0.0, 4, ByteOrder::LittleEndian),
+            [0x30, 0x12, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            signal.calculate_frame(0.0, 7, ByteOrder::BigEndian),
+            [0x12, 0x30, 0, 0, 0, 0, 0, 0]
+        );
+
+        // Negative counts are packed as two's complement within the signal's bits
+        let signal = generators::Constant {
+            is_signed: true,
+            offset: 1.0,
+            ..signal
+        };
+        assert_eq!(
+            signal.calculate_frame(0.0, 0, ByteOrder::LittleEndian),
+            [0xFF, 0x0F, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn packing_debug_test() {
+        use super::*;
+        use crate::can_frame::ByteOrder;
+
+        let signal = generators::Constant {
+            num_bits: 12,
+            is_signed: false,
+            ..generators::test_constant(0.0)
+        };
+
+        assert_eq!(
+            signal.packing_debug(0x123, 4, ByteOrder::LittleEndian),
+            "value 291 (0x123) in 12 bits, LittleEndian\n\
+             LSB at bit 4 (byte 0 bit 4), MSB at bit 15 (byte 1 bit 7), bytes 0-1\n\
+             frame: 30 12 00 00 00 00 00 00"
+        );
+        assert_eq!(
+            signal.packing_debug(0x123, 7, ByteOrder::BigEndian),
+            "value 291 (0x123) in 12 bits, BigEndian\n\
+             LSB at bit 12 (byte 1 bit 4), MSB at bit 7 (byte 0 bit 7), bytes 0-1\n\
+             frame: 12 30 00 00 00 00 00 00"
+        );
+
+        // Negative counts are shown as the two's complement that is packed
+        let debug = signal.packing_debug(-1, 60, ByteOrder::LittleEndian);
+        assert!(debug.starts_with("value -1 (0xFFF) in 12 bits"), "{debug}");
+        assert!(
+            debug.contains("MSB at bit 71 (outside the frame, dropped)"),
+            "{debug}"
+        );
+    }
+
+    #[test]
+    fn period_mean_test() {
+        use super::*;
+        use crate::signal_generator::SignalGenerator;
+
+        for signal_type in SignalType::get_types() {
+            let signal = SignalGenerator::new(
+                signal_type,
+                get_min_limit(),
+                get_max_limit(),
+                1000.0,
+                2.0,
+                0.3,
+                16,
+                true,
+                1.0,
+                0.0,
+            )
+            .unwrap()
+            .inner;
+
+            let expected = match signal_type {
+                SignalType::Constant => 1000.0,
+                _ => 0.0,
+            };
+            assert_eq!(signal.period_mean(), expected);
+
+            // Average the (noisy) waveform over exactly one period
+            let count = 10_000;
+            let mean = (0..count)
+                .map(|i| signal.calculate_float(i as f64 * 2.0 / count as f64))
+                .sum::<f64>()
+                / count as f64;
+            assert!((mean - expected).abs() < 5.0, "{signal_type}: {mean}");
+        }
+    }
+
+    #[test]
+    fn enob_test() {
+        use super::*;
+        use std::f64::consts::PI;
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let sine = || generators::Sine {
+            num_bits: 12,
+            ..generators::test_sine(2047.0, 1.0)
+        };
+
+        let deterministic = generators::Deterministic::enable();
+        let noiseless = sine().enob(0.0, 10.0, 0.0013);
+        drop(deterministic);
+        assert!((noiseless - 12.0).abs() < 0.3, "{noiseless}");
+
+        let noisy = sine().enob(0.0, 10.0, 0.0013);
+        let noisier = with_gaussian_noise(|time| 2047.0 * (2.0 * PI * time).sin(), 50.0, 12)
+            .enob(0.0, 10.0, 0.0013);
+        assert!(noisy < noiseless - 1.0, "{noisy}");
+        assert!(noisier < noisy - 1.0, "{noisier}");
+    }
+
+    #[test]
+    fn noise_free_bits_test() {
+        use super::*;
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let sine = |amplitude| generators::test_sine(amplitude, 1.0);
+
+        let noisiest = sine(10000.0).noise_free_bits(0.0, 10.0, 0.001);
+        let noisy = sine(1000.0).noise_free_bits(0.0, 10.0, 0.001);
+        let quiet = sine(100.0).noise_free_bits(0.0, 10.0, 0.001);
+        let deterministic = generators::Deterministic::enable();
+        let silent = sine(1000.0).noise_free_bits(0.0, 10.0, 0.001);
+        drop(deterministic);
+
+        // Uniform noise within ±10 LSBs has an RMS of 10/√3, so 38 LSBs peak to peak
+        let expected = 16.0 - (6.6 * 10.0 / 3_f64.sqrt()).log2();
+        assert!((noisy - expected).abs() < 0.05, "{noisy}");
+
+        // The noise is a fraction of the amplitude, so ten times the amplitude costs log2(10) bits
+        assert!(
+            (noisiest - (noisy - 10_f64.log2())).abs() < 0.05,
+            "{noisiest}"
+        );
+        assert!((quiet - (noisy + 10_f64.log2())).abs() < 0.05, "{quiet}");
+        assert_eq!(silent, 16.0);
+    }
+
+    #[test]
+    fn with_engineering_range_test() {
+        use super::*;
+
+        let signal = generators::Sine {
+            num_bits: 12,
+            is_signed: false,
+            ..generators::test_sine(50.0, 1.0)
+        }
+        .with_engineering_range(-40.0, 125.0);
+
+        assert!((signal.full_scale() - 125.0).abs() < 1e-9);
+        assert!((signal.zero_scale() - -40.0).abs() < 1e-9);
+        assert_eq!(signal.minimum, -40.0);
+        assert_eq!(signal.maximum, 125.0);
+
+        let signal = generators::Sine {
+            is_signed: true,
+            ..signal
+        }
+        .with_engineering_range(-10.0, 10.0);
+
+        assert!((signal.full_scale() - 10.0).abs() < 1e-9);
+        assert!((signal.zero_scale() - -10.0).abs() < 1e-9);
+
+        // Too coarse for the bits, so one unit per count from the bottom of the range
+        let signal = generators::Sine {
+            num_bits: 8,
+            ..signal
+        }
+        .with_engineering_range(0.0, 10000.0);
+
+        assert_eq!(signal.scale, 1.0);
+        assert_eq!(signal.zero_scale(), 0.0);
+        assert_eq!(signal.full_scale(), 255.0);
+        assert_eq!(signal.maximum, 255.0);
+        assert_eq!(signal.verify_lsb(), 1.0);
+    }
+
+    #[test]
+    fn with_reference_phase_test() {
+        use super::*;
+        use std::f64::consts::PI;
+
+        let sine = |period: f64| generators::Sine {
+            phase: 0.37,
+            ..generators::test_sine(1000.0, period)
+        };
+
+        for (reference_time, desired) in [(0.0, 0.0), (2.3, PI / 3.0), (-7.1, 1.9 * PI)] {
+            let signal = sine(0.02).with_reference_phase(reference_time, desired);
+            let fraction = generators::cycle_fraction(reference_time, signal.phase, signal.period);
+            assert!(
+                (2.0 * PI * fraction - desired).abs() < 1e-6,
+                "{reference_time}: {fraction}"
+            );
+            assert!(
+                (signal.calculate_noiseless(reference_time) - 1000.0 * desired.sin()).abs() < 1e-3
+            );
+        }
+
+        // Phases outside one cycle wrap round to the same point
+        let wrapped = sine(0.02).with_reference_phase(1.0, -PI / 2.0);
+        assert!((wrapped.calculate_noiseless(1.0) - -1000.0).abs() < 1e-6);
+
+        // Without a period there's no cycle to line up
+        assert_eq!(sine(0.0).with_reference_phase(1.0, PI).phase, 0.37);
+    }
+
+    #[test]
+    fn test_pattern_test() {
+        use super::*;
+
+        let pattern = generators::TestPattern {
+            period: 2.0,
+            num_bits: 8,
+            is_signed: true,
+            scale: 0.5,
+            offset: 3.0,
+        };
+
+        for turn in 0..10 {
+            let (_, count) = pattern.calculate_pair(turn as f64);
+            let expected = if turn % 2 == 0 { -128 } else { 127 };
+            assert_eq!(count, expected);
+        }
+
+        // Every count in between is within range and repeatable
+        let (_, counts) = pattern.calculate_range_pairs(0.0, 4.0, 0.01);
+        let (_, again) = pattern.calculate_range_pairs(0.0, 4.0, 0.01);
+        assert_eq!(counts, again);
+        assert!(counts.iter().all(|count| (-128..=127).contains(count)));
+
+        // It averages to the middle of the range, signed or not
+        for is_signed in [true, false] {
+            let pattern = generators::TestPattern {
+                is_signed,
+                ..pattern
+            };
+            let count = 100_000;
+            let mean = (0..count)
+                .map(|i| pattern.calculate_float(i as f64 * 2.0 / count as f64))
+                .sum::<f64>()
+                / count as f64;
+            assert!((pattern.period_mean() - mean).abs() < 0.01, "{mean}");
+        }
+
+        // It isn't a plain triangle, so has no spec or phase to compare
+        assert_eq!(pattern.waveform_spec(), None);
+        assert_eq!(generators::phase_difference(&pattern, &pattern), None);
+    }
+
+    #[test]
+    fn code_sweep_test() {
+        use super::*;
+
+        let sweep = |is_signed| generators::CodeSweep {
+            sample_step: 0.001,
+            samples_per_code: 3,
+            num_bits: 6,
+            is_signed,
+            scale: 0.5,
+            offset: 3.0,
+        };
+
+        for (is_signed, first) in [(true, -32), (false, 0)] {
+            let sweep = sweep(is_signed);
+            assert!((sweep.get_period() - 0.192).abs() < 1e-9);
+
+            // One full sweep, then the start of the next
+            let (_, counts) = sweep.calculate_range_pairs(0.0, 0.195, 0.001);
+            for code in first..first + 64 {
+                let times = counts[..192].iter().filter(|&&count| count == code).count();
+                assert_eq!(times, 3, "{code}");
+            }
+            assert!(counts[..192].windows(2).all(|pair| pair[1] >= pair[0]));
+            assert_eq!(counts[..3], [first; 3]);
+            assert_eq!(counts[192..], [first; 3]);
+
+            // Each code is held as long as the others, so the mean is the middle of the range
+            let mean = counts[..192]
+                .iter()
+                .map(|&count| sweep.count_to_value(count))
+                .sum::<f64>()
+                / 192.0;
+            assert!((sweep.period_mean() - mean).abs() < 1e-9, "{mean}");
+        }
+
+        // The widest sweeps have one more code than an i64 can count
+        for (num_bits, is_signed, first) in [(64, true, i64::MIN), (63, false, 0)] {
+            let sweep = generators::CodeSweep {
+                num_bits,
+                ..sweep(is_signed)
+            };
+            assert_eq!(sweep.count_at(0.0), first);
+            assert_eq!(sweep.count_at(0.003), first + 1);
+            assert!(sweep.count_at(-0.003) > first);
+            assert!(sweep.get_period() > 0.0);
+        }
+    }
+
+    #[test]
+    fn phase_difference_test() {
+        use super::*;
+        use std::f64::consts::PI;
+
+        let sine = |period, phase| generators::Sine {
+            phase,
+            ..generators::test_sine(10.0, period)
+        };
+
+        let difference = generators::phase_difference(&sine(4.0, 1.0), &sine(4.0, 0.0)).unwrap();
+        assert!((difference - PI / 2.0).abs() < 1e-12);
+
+        let difference = generators::phase_difference(&sine(4.0, 0.0), &sine(4.0, 1.0)).unwrap();
+        assert!((difference + PI / 2.0).abs() < 1e-12);
+
+        // A full period apart is in phase
+        let difference = generators::phase_difference(&sine(4.0, 4.0), &sine(4.0, 0.0)).unwrap();
+        assert!(difference.abs() < 1e-12);
+
+        assert_eq!(
+            generators::phase_difference(&sine(4.0, 0.0), &sine(3.0, 0.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn dither_to_bits_test() {
+        use super::*;
+
+        // A zero amplitude constant has no noise, so its raw count is exactly -offset
+        let signal = generators::Constant {
+            is_signed: false,
+            offset: -100.3,
+            ..generators::test_constant(0.0)
+        };
+
+        // 100.3 counts at 16 bits is 0.3918 counts at 8 bits
+        let exact: f64 = 100.3 / 256.0;
+        let truncated = (exact.floor() - exact).abs();
+
+        let count = 10_000;
+        let mean = (0..count)
+            .map(|_| signal.dither_to_bits(0.0, 8) as f64)
+            .sum::<f64>()
+            / count as f64;
+
+        assert!((mean - exact).abs() < 0.05, "{mean}");
+        assert!((mean - exact).abs() < truncated);
+    }
+
+    #[test]
+    fn window_checksum_test() {
+        use super::*;
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let sine = |amplitude, period, phase, num_bits| generators::Sine {
+            phase,
+            num_bits,
+            ..generators::test_sine(amplitude, period)
+        };
+
+        let checksum = sine(1000.0, 1.0, 0.0, 16).window_checksum(0.0, 2.0, 0.01, 42);
+        assert_eq!(
+            sine(1000.0, 1.0, 0.0, 16).window_checksum(0.0, 2.0, 0.01, 42),
+            checksum
+        );
+
+        for changed in [
+            sine(1001.0, 1.0, 0.0, 16),
+            sine(1000.0, 1.1, 0.0, 16),
+            sine(1000.0, 1.0, 0.1, 16),
+            sine(1000.0, 1.0, 0.0, 8),
+        ] {
+            assert_ne!(changed.window_checksum(0.0, 2.0, 0.01, 42), checksum);
+        }
+        assert_ne!(
+            sine(1000.0, 1.0, 0.0, 16).window_checksum(0.0, 2.0, 0.01, 43),
+            checksum
+        );
+    }
+
+    #[test]
+    fn from_params_test() {
+        use super::*;
+
+        let params: generators::SignalParams = serde_json::from_str(
+            r#"{
+                "minimum": -1000.0,
+                "maximum": 1000.0,
+                "amplitude": 500.0,
+                "period": 2.0,
+                "phase": 0.5,
+                "num_bits": 16,
+                "is_signed": true,
+                "scale": 1.0,
+                "offset": 0.0
+            }"#,
+        )
+        .unwrap();
+
+        for signal_type in SignalType::get_types() {
+            let signal = generators::from_params(signal_type, &params);
+            assert_eq!(signal.get_type(), signal_type);
+            assert_eq!(signal.get_amplitude(), 500.0);
+            assert_eq!(signal.get_phase(), 0.5);
+
+            let values = signal.calculate_range(0.0, 4.0, 0.1);
+            assert_eq!(values.len(), 40);
+            assert!(values.iter().all(|value| value.abs() <= 510));
+        }
+    }
+
+    #[test]
+    fn calculate_budgeted_test() {
+        use super::*;
+
+        let signal = generators::test_sine(100.0, 1.0);
+
+        let (values, step) = signal.calculate_budgeted(0.0, 10.0, 100);
+        assert!(values.len() <= 100);
+        assert!((step - 0.1).abs() < 1e-12);
+
+        let (values, step) = signal.calculate_budgeted(0.0, 10.0, 7);
+        assert!(values.len() <= 7);
+        assert!(step * 7.0 >= 10.0 - 1e-12);
+
+        assert_eq!(signal.calculate_budgeted(0.0, 10.0, 0), (Vec::new(), 0.0));
+    }
+
+    #[test]
+    fn produces_same_output_test() {
+        use super::*;
+
+        let sine = |phase| generators::Sine {
+            phase,
+            ..generators::test_sine(1000.0, 2.0)
+        };
+
+        assert!(sine(0.0).produces_same_output(&sine(2.0), 0.0, 4.0, 0.01));
+        assert!(sine(0.5).produces_same_output(&sine(-1.5), 0.0, 4.0, 0.01));
+        assert!(!sine(0.0).produces_same_output(&sine(0.5), 0.0, 4.0, 0.01));
+    }
+
+    #[test]
+    fn period_table_test() {
+        use super::*;
+
+        let sine = generators::test_sine(1000.0, 4.0);
+
+        assert_eq!(
+            sine.period_table(),
+            vec![(0.0, 0), (25.0, 1000), (50.0, 0), (75.0, -1000), (100.0, 0)]
+        );
+    }
+
+    #[test]
+    fn ac_component_test() {
+        use super::*;
+
+        let constant = generators::Constant {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            period: 1.0,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 500.0,
+        };
+        for i in 0..100 {
+            assert!(constant.ac_component(i as f64 * 0.1).abs() <= 10.0);
+        }
+
+        let sine = generators::Sine {
+            offset: 500.0,
+            ..generators::test_sine(1000.0, 1.0)
+        };
+        let ac: Vec<f64> = (0..1000)
+            .map(|i| sine.ac_component(i as f64 * 0.001))
+            .collect();
+        let mean = ac.iter().sum::<f64>() / ac.len() as f64;
+        let max = ac.iter().cloned().fold(f64::MIN, f64::max);
+        let min = ac.iter().cloned().fold(f64::MAX, f64::min);
+        assert!(mean.abs() < 5.0);
+        assert!((max + min).abs() < 25.0);
+    }
+
+    #[test]
+    fn waveform_spec_test() {
+        use super::*;
+
+        let constant = generators::Constant {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 12.0,
+            period: 3.0,
+            phase: 1.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        assert_eq!(
+            constant.waveform_spec(),
+            Some(generators::WaveformSpec::Constant { amplitude: 12.0 })
+        );
+
+        let sine = generators::Sine {
+            phase: 1.0,
+            ..generators::test_sine(12.0, 3.0)
+        };
+        assert_eq!(
+            sine.waveform_spec(),
+            Some(generators::WaveformSpec::Sine {
+                amplitude: 12.0,
+                period: 3.0,
+                phase: 1.0
+            })
+        );
+
+        // A wrapper's forwarded getters only describe what it wraps
+        let hummed = sine.with_mains_hum(60.0, 0.1);
+        assert_eq!(hummed.waveform_spec(), None);
+    }
+
+    #[test]
+    fn no_panic_fuzz_test() {
+        use super::*;
+        use crate::can_frame::ByteOrder;
+        use crate::signal_generator::SignalGenerator;
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        // Deliberately awkward configurations: bit widths outside 1 to 64, zero or negative
+        // periods and scales, and limits that are the wrong way round
+        let mut rng = StdRng::seed_from_u64(229);
+        for _ in 0..2000 {
+            let pick = |rng: &mut StdRng, values: &[f64]| values[rng.gen_range(0..values.len())];
+            let params = generators::SignalParams {
+                minimum: rng.gen_range(-1e6..1e6),
+                maximum: rng.gen_range(-1e6..1e6),
+                amplitude: pick(&mut rng, &[0.0, 1.0, -5.0, 1e9]),
+                period: pick(&mut rng, &[0.0, -1.0, 1e-9, 0.5, 1e9]),
+                phase: rng.gen_range(-10.0..10.0),
+                num_bits: rng.gen_range(0..=70),
+                is_signed: rng.gen(),
+                scale: pick(&mut rng, &[0.0, -0.1, 0.01, 1.0, 1e6]),
+                offset: rng.gen_range(-1e3..1e3),
+            };
+            let signal_type = SignalType::iter().nth(rng.gen_range(0..5)).unwrap();
+            let signal = generators::from_params(signal_type, &params);
+
+            for time in [-1e6, -1.0, 0.0, 0.25, 1.0, 1e12] {
+                signal.calculate(time);
+                signal.calculate_pair(time);
+                signal.calculate_frame(time, 60, ByteOrder::BigEndian);
+                signal.dither_to_bits(time, 8);
+                signal.packing_debug(signal.calculate(time), 60, ByteOrder::LittleEndian);
+                assert!(
+                    !signal.calculate_float(time).is_nan(),
+                    "{signal_type} {params:?} at {time}"
+                );
+            }
+
+            // The Python constructor refuses limits the wrong way round rather than panicking
+            let generator = SignalGenerator::new(
+                signal_type,
+                params.minimum,
+                params.maximum,
+                params.amplitude,
+                params.period,
+                params.phase,
+                params.num_bits,
+                params.is_signed,
+                params.scale,
+                params.offset,
+            );
+            match generator {
+                Ok(generator) => {
+                    assert!(params.minimum <= params.maximum);
+                    generator.calculate_range(0.0, 1.0, 0.25);
+                }
+                Err(_) => assert!(params.minimum > params.maximum),
+            }
+        }
+    }
+
+    #[test]
+    fn to_svg_path_test() {
+        use super::*;
+
+        let sine = generators::test_sine(1000.0, 1.0);
+
+        let path = sine.to_svg_path(0.0, 1.0, 0.01, 200.0, 100.0);
+        assert!(path.starts_with("M0.00 "));
+        assert_eq!(path.matches('M').count(), 1);
+        assert_eq!(path.matches('L').count(), 99);
+
+        // A flat trace sits in the middle of the box
+        let constant = generators::Constant {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 0.0,
+            period: 1.0,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        assert_eq!(
+            constant.to_svg_path(0.0, 1.0, 0.5, 200.0, 100.0),
+            "M0.00 50.00 L100.00 50.00"
+        );
+    }
+
+    #[cfg(feature = "wav")]
+    #[test]
+    fn write_wav_test() {
+        use super::*;
+
+        let sine = generators::test_sine(1000.0, 0.01);
+
+        let path = std::env::temp_dir().join("write_wav_test.wav");
+        sine.write_wav(&path, 0.5, 8000).unwrap();
+        let wav = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 8000);
+        let data_len = u32::from_le_bytes(wav[40..44].try_into().unwrap());
+        assert_eq!(data_len, 4000 * 2);
+        assert_eq!(wav.len(), 44 + 4000 * 2);
+    }
+
+    #[cfg(feature = "npy")]
+    #[test]
+    fn write_npy_test() {
+        use super::*;
+
+        let ramp =
+            generators::Callback::new(|time| time * 100.0, -1000.0, 1000.0, 16, true, 1.0, 0.0);
+
+        let path = std::env::temp_dir().join("write_npy_test.npy");
+        ramp.write_npy(&path, -0.05, 0.05, 0.01).unwrap();
+        let npy = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // A minimal reader: the magic and version, then the header, then the data
+        assert_eq!(&npy[0..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        let data_start = 10 + header_len;
+        assert_eq!(data_start % 64, 0);
+        let header = std::str::from_utf8(&npy[10..data_start]).unwrap();
+        assert!(header.starts_with("{'descr': '<i8', 'fortran_order': False, 'shape': (10,), }"));
+        assert!(header.ends_with('\n'));
+
+        let values: Vec<i64> = npy[data_start..]
+            .chunks_exact(8)
+            .map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        assert_eq!(values, [-5, -4, -3, -2, -1, 0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn window_extremes_test() {
+        use super::*;
+
+        let sine = |amplitude: f64, rail: f64| generators::Sine {
+            minimum: -rail,
+            maximum: rail,
+            ..generators::test_sine(amplitude, 1.0)
+        };
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let deterministic = generators::Deterministic::enable();
+        let free = sine(500.0, 1000.0).window_extremes(0.0, 1.0, 0.001);
+        let clamped = sine(1000.0, 800.0).window_extremes(0.0, 1.0, 0.001);
+        let empty = sine(500.0, 1000.0).window_extremes(1.0, 1.0, 0.001);
+        drop(deterministic);
+
+        assert_eq!(free, (-500, 500));
+        assert_eq!(clamped, (-800, 800));
+        assert_eq!(empty, (i64::MAX, i64::MIN));
+    }
+
+    #[test]
+    fn snap_to_nice_test() {
+        use super::*;
+
+        for (resolution, step) in [
+            (0.3, 0.5),
+            (0.12, 0.2),
+            (0.002, 0.002),
+            (1.0, 1.0),
+            (7.0, 10.0),
+            (1500.0, 2000.0),
+        ] {
+            let nice = generators::nice_step(resolution);
+            assert!(
+                (nice - step).abs() < step * 1e-9,
+                "{resolution} gave {nice}"
+            );
+        }
+
+        // Callbacks have no noise, so each value is exactly its time
+        let ramp = |scale: f64| {
+            generators::Callback::new(|time| time, -1000.0, 1000.0, 16, true, scale, 0.0)
+        };
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        // An LSB of 0.3 snaps to steps of 0.5
+        assert!(close(ramp(0.3).snap_to_nice(1.2), 1.0));
+        assert!(close(ramp(0.3).snap_to_nice(1.3), 1.5));
+        assert!(close(ramp(0.3).snap_to_nice(-2.74), -2.5));
+
+        // An LSB of 0.015 snaps to steps of 0.02
+        assert!(close(ramp(0.015).snap_to_nice(0.115), 0.12));
+
+        // An LSB that is already nice stays as it is
+        assert!(close(ramp(1.0).snap_to_nice(12.4), 12.0));
+    }
+
+    #[test]
+    fn waveform_value_test() {
+        use super::*;
+
+        let params = generators::SignalParams {
+            minimum: -1000.0,
+            maximum: 1000.0,
+            amplitude: 700.0,
+            period: 0.4,
+            phase: 0.13,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        // Each waveform's value from its amplitude, period, phase and time
+        type Waveform = fn(f64, f64, f64, f64) -> f64;
+        let waveforms: [(SignalType, Waveform); 5] = [
+            (SignalType::Sine, generators::sine_value),
+            (SignalType::Square, generators::square_value),
+            (SignalType::Triangle, generators::triangle_value),
+            (SignalType::Sawtooth, generators::sawtooth_value),
+            (SignalType::Constant, generators::constant_value),
+        ];
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let deterministic = generators::Deterministic::enable();
+        let mismatches: Vec<_> = waveforms
+            .iter()
+            .flat_map(|&(signal_type, value)| {
+                let signal = generators::from_params(signal_type, &params);
+                generators::sample_times(-1.0, 1.0, 0.0037)
+                    .filter(move |&time| {
+                        signal.calculate_float(time)
+                            != value(params.amplitude, params.period, params.phase, time)
+                    })
+                    .map(move |time| (signal_type, time))
+            })
+            .collect();
+        drop(deterministic);
+
+        assert!(mismatches.is_empty(), "{mismatches:?}");
+    }
+
+    #[test]
+    fn coefficient_of_variation_test() {
+        use super::*;
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let constant = |amplitude: f64| generators::test_constant(amplitude);
+
+        // Uniform noise within ±NOISE_LEVEL of the amplitude has a deviation of NOISE_LEVEL/√3 of it
+        let cv = constant(1000.0).coefficient_of_variation(0.0, 100.0, 0.01);
+        let expected = generators::NOISE_LEVEL / 3_f64.sqrt();
+        assert!((cv - expected).abs() < expected * 0.1, "{cv}");
+
+        assert!(constant(0.0)
+            .coefficient_of_variation(0.0, 1.0, 0.01)
+            .is_nan());
+        // A mean under one LSB is as good as zero
+        assert!(constant(0.4)
+            .coefficient_of_variation(0.0, 1.0, 0.01)
+            .is_nan());
+        assert!(!constant(2.0)
+            .coefficient_of_variation(0.0, 1.0, 0.01)
+            .is_nan());
+    }
+
+    #[test]
+    fn moments_test() {
+        use super::*;
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let constant = || generators::test_constant(1000.0);
+
+        // The built-in noise is uniform
+        let uniform = constant();
+        let kurtosis = uniform.kurtosis(0.0, 100.0, 0.001);
+        let skewness = uniform.skewness(0.0, 100.0, 0.001);
+        assert!((kurtosis - 1.8).abs() < 0.05, "{kurtosis}");
+        assert!(skewness.abs() < 0.05, "{skewness}");
+
+        // Callbacks have no noise of their own
+        let gaussian = with_gaussian_noise(|_| 1000.0, 100.0, 16);
+        let kurtosis = gaussian.kurtosis(0.0, 100.0, 0.001);
+        let skewness = gaussian.skewness(0.0, 100.0, 0.001);
+        assert!((kurtosis - 3.0).abs() < 0.1, "{kurtosis}");
+        assert!(skewness.abs() < 0.05, "{skewness}");
+
+        // The square of a uniform ramp bunches up near 0, with a skewness of about 0.64
+        let squared = generators::Callback::new(
+            |time| 1000.0 * time.fract().powi(2),
+            -2000.0,
+            2000.0,
+            16,
+            true,
+            1.0,
+            0.0,
+        );
+        let skewness = squared.skewness(0.0, 10.0, 0.0001);
+        assert!((skewness - 0.64).abs() < 0.01, "{skewness}");
+
+        let deterministic = generators::Deterministic::enable();
+        let flat = constant().kurtosis(0.0, 1.0, 0.01);
+        drop(deterministic);
+        assert!(flat.is_nan());
+    }
+
+    #[test]
+    fn callback_test() {
+        use super::*;
+
+        let doubled = generators::Callback::new(
+            |time| time * 2.0,
+            get_min_limit(),
+            get_max_limit(),
+            16,
+            true,
+            1.0,
+            0.0,
+        );
+        for i in 0..100 {
+            let time = i as f64 * 1.5;
+            assert_eq!(doubled.calculate(time), (time * 2.0).round() as i64);
+        }
+
+        // The callback can carry state between calls, and its output is still clamped
+        let mut calls = 0.0;
+        let counting = generators::Callback::new(
+            move |_| {
+                calls += 100.0;
+                calls
+            },
+            0.0,
+            250.0,
+            16,
+            true,
+            1.0,
+            0.0,
+        );
+        assert_eq!(counting.calculate(0.0), 100);
+        assert_eq!(counting.calculate(0.0), 200);
+        assert_eq!(counting.calculate(0.0), 250);
+
+        // Its limits describe it, not the amplitude and period it doesn't have
+        assert!(counting.period_mean().is_nan());
+        assert_eq!(counting.recommended_bits(0.0), 8);
+        assert!(counting.validate().is_empty());
+        assert_eq!(counting.waveform_spec(), None);
+        assert_eq!(counting.encode_config(), None);
+        let narrow = generators::Callback::new(|_| 0.0, 0.0, 0.5, 16, true, 1.0, 0.0);
+        assert_eq!(narrow.validate().len(), 1);
+
+        // The AC component takes out the running mean of whatever it returns
+        let ramp =
+            generators::Callback::new(|time| time * 100.0, -1000.0, 1000.0, 16, true, 1.0, 0.0);
+        assert!((ramp.ac_component(4.0) - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn calculate_q_test() {
+        use super::*;
+
+        let constant = |amplitude: f64| generators::test_constant(amplitude);
+
+        let encoded = constant(1.5).calculate_q(0.0, 4, 4);
+        assert_eq!(encoded, 24);
+        assert_eq!(encoded as f64 / 16.0, 1.5);
+
+        // Q4.4 saturates at just under ±16
+        assert_eq!(constant(100.0).calculate_q(0.0, 4, 4), 255);
+        assert_eq!(constant(-100.0).calculate_q(0.0, 4, 4), -256);
+    }
+
+    #[test]
+    fn max_slew_rate_test() {
+        use super::*;
+        use std::f64::consts::PI;
+
+        let params = generators::SignalParams {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            period: 2.0,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        let slew = |signal_type| generators::from_params(signal_type, &params).max_slew_rate();
+
+        assert!((slew(SignalType::Sine) - 1000.0 * PI).abs() < 1e-9);
+        assert_eq!(slew(SignalType::Triangle), 2000.0);
+        assert_eq!(slew(SignalType::Sawtooth), 1000.0);
+        assert_eq!(slew(SignalType::Square), 0.0);
+
+        // The steepest step between noiseless samples of the sine matches
+        let sine = generators::from_params(SignalType::Sine, &params);
+        let step = 1e-4;
+        let steepest = (0..20000)
+            .map(|i| {
+                let time = i as f64 * step;
+                (sine.calculate_noiseless(time + step) - sine.calculate_noiseless(time)) / step
+            })
+            .fold(0.0, |max: f64, slope| max.max(slope.abs()));
+        assert!((steepest - 1000.0 * PI).abs() < 0.01);
+    }
+
+    #[test]
+    fn recommended_bits_test() {
+        use super::*;
+
+        // A span of 255 counts exactly fills 8 bits
+        let sine = generators::test_sine(127.5, 1.0);
+        assert_eq!(sine.recommended_bits(0.0), 8);
+        assert_eq!(sine.recommended_bits(0.2), 9);
+    }
+
+    #[test]
+    fn delta_samples_test() {
+        use super::*;
+
+        let constant = generators::test_constant(1000.0);
+        // The noise is within ±10 counts
+        assert_eq!(constant.delta_samples(0.0, 10.0, 0.01, 50).len(), 1);
+
+        // A ramp of one count per step changes by more than 9 counts every 10 steps
+        let ramp = generators::Callback::new(
+            |time| time * 100.0,
+            get_min_limit(),
+            get_max_limit(),
+            16,
+            true,
+            1.0,
+            0.0,
+        );
+        let deltas = ramp.delta_samples(0.0, 1.0, 0.01, 9);
+        assert_eq!(deltas.len(), 10);
+        for (i, &(time, value)) in deltas.iter().enumerate() {
+            assert!((time - i as f64 * 0.1).abs() < 1e-9);
+            assert_eq!(value, i as i64 * 10);
+        }
+    }
+
+    #[test]
+    fn validate_test() {
+        use super::*;
+
+        let sine = |amplitude: f64, scale: f64| generators::Sine {
+            scale,
+            ..generators::test_sine(amplitude, 1.0)
+        };
+
+        assert_eq!(
+            sine(0.05, 0.1).validate(),
+            vec![generators::SignalWarning::SubLsbAmplitude {
+                amplitude: 0.05,
+                lsb: 0.1
+            }]
+        );
+        assert!(sine(0.05, 0.01).validate().is_empty());
+        assert!(sine(1000.0, 1.0).validate().is_empty());
+    }
+
+    #[test]
+    fn validate_all_test() {
+        use super::*;
+
+        let sine = |amplitude: f64, scale: f64| generators::Sine {
+            scale,
+            ..generators::test_sine(amplitude, 1.0)
+        };
+        let (good, tiny, coarse) = (sine(1000.0, 1.0), sine(0.05, 0.1), sine(0.5, 1.0));
+
+        let report = generators::validate_all(&[
+            ("good", &good),
+            ("tiny", &tiny),
+            ("also good", &sine(0.05, 0.01)),
+            ("coarse", &coarse),
+        ]);
+        let names: Vec<_> = report.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["tiny", "coarse"]);
+        assert_eq!(report[0].1, tiny.validate());
+        assert_eq!(report[1].1, coarse.validate());
+        assert!(generators::validate_all(&[("good", &good)]).is_empty());
+    }
+
+    #[test]
+    fn impulse_train_test() {
+        use super::*;
+        use generators::sample_times;
+
+        let train = |sample_step: f64, phase: f64| generators::ImpulseTrain {
+            minimum: 0.0,
+            maximum: 2000.0,
+            amplitude: 1000.0,
+            period: 1.0,
+            phase,
+            sample_step,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+
+        for (step, phase) in [(0.01, 0.0), (0.03, 0.5), (0.07, 0.3), (0.001, -0.25)] {
+            let train = train(step, phase);
+            let impulses: Vec<f64> = sample_times(0.0, 10.0, step)
+                .filter(|&time| train.calculate_clean(time) == 1000)
+                .collect();
+            assert_eq!(impulses.len(), 10, "step {step}, phase {phase}");
+            assert!(sample_times(0.0, 10.0, step)
+                .all(|time| train.is_impulse(time) || train.calculate_clean(time) == 0));
+
+            // One sample in a period's worth is at the amplitude, near enough when the step
+            // doesn't divide the period
+            let samples: Vec<f64> = sample_times(0.0, 10.0, step)
+                .map(|time| train.calculate_noiseless(time))
+                .collect();
+            let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+            assert!((train.period_mean() - mean).abs() < 1.0, "{mean}");
+        }
+
+        let train = train(0.01, 0.0);
+        assert_eq!(train.recommended_bits(0.0), 10);
+        assert_eq!(train.waveform_spec(), None);
+        assert_eq!(train.encode_config(), None);
+    }
+
+    #[test]
+    fn calculate_checked_test() {
+        use super::*;
+
+        let constant = |amplitude: f64, num_bits: u8| generators::Constant {
+            minimum: -500.0,
+            maximum: 500.0,
+            amplitude,
+            period: 0.0,
+            phase: 0.0,
+            num_bits,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+
+        let (value, clamped) = constant(100.0, 16).calculate_checked(0.0);
+        assert!((value - 100).abs() <= 1);
+        assert!(!clamped);
+
+        // Past the maximum
+        assert_eq!(constant(1000.0, 16).calculate_checked(0.0), (500, true));
+        // Past what 8 bits can hold
+        assert_eq!(constant(300.0, 8).calculate_checked(0.0), (127, true));
+    }
+
+    #[test]
+    fn distinct_levels_test() {
+        use super::*;
+
+        let sine = |amplitude: f64| generators::test_sine(amplitude, 1.0);
+
+        // ±10 with a little noise can only reach a couple of dozen of the 65536 codes
+        let quiet = sine(10.0).distinct_levels(0.0, 1.0, 0.0001);
+        assert!((15..=25).contains(&quiet), "{quiet}");
+
+        let loud = sine(10000.0).distinct_levels(0.0, 1.0, 0.0001);
+        assert!(loud > 100 * quiet, "{loud}");
+        assert!(loud <= 1 << 16);
+    }
+
+    #[test]
+    fn crossing_rate_test() {
+        use super::*;
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let _deterministic = generators::Deterministic::enable();
+
+        // 4 Hz, starting just after a crossing so the window doesn't begin on one
+        let sine = generators::Sine {
+            phase: 0.01,
+            ..generators::test_sine(1000.0, 0.25)
+        };
+        assert_eq!(sine.crossing_rate(0.0, 10.0, 0.001, 0.0), 8.0);
+    }
+
+    #[test]
+    fn for_display_test() {
+        use super::*;
+
+        // A period per column, far too fast to plot sample by sample
+        let sine = generators::test_sine(1000.0, 0.01);
+
+        let columns = sine.for_display(0.0, 1.0, 100);
+        assert_eq!(columns.len(), 100);
+        for (low, high) in columns {
+            assert!((low + 1000).abs() <= 20, "{low}");
+            assert!((high - 1000).abs() <= 20, "{high}");
+        }
+    }
+
+    #[test]
+    fn rms_with_noise_test() {
+        use super::*;
+        use std::f64::consts::PI;
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        // Enough noise to stand out from the sine
+        let noisy = with_gaussian_noise(|time| 1000.0 * (2.0 * PI * time).sin(), 60.0, 16);
+
+        let rms = generators::with_seed(257, || noisy.rms_with_noise(0.0, 100.0, 0.0005));
+        let rms_clean_squared = 1000.0_f64.powi(2) / 2.0;
+        let excess = rms.powi(2) - rms_clean_squared;
+        let variance = 60.0 * 60.0;
+        assert!(
+            (excess - variance).abs() < variance * 0.1,
+            "{excess} vs {variance}"
+        );
+
+        // Reproducible under a seed
+        let again = generators::with_seed(257, || noisy.rms_with_noise(0.0, 100.0, 0.0005));
+        assert_eq!(rms, again);
+    }
+
+    #[test]
+    fn calculate_with_bit_errors_test() {
+        use super::*;
+
+        let constant = |is_signed: bool| generators::Constant {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 0.0,
+            period: 0.0,
+            phase: 0.0,
+            num_bits: 8,
+            is_signed,
+            scale: 1.0,
+            // A raw count of 0b0101_0011
+            offset: -83.0,
+        };
+
+        let unsigned = constant(false);
+        let signed = constant(true);
+        generators::with_seed(258, || {
+            for _ in 0..100 {
+                assert_eq!(unsigned.calculate_with_bit_errors(0.0, 0.0), 0b0101_0011);
+                assert_eq!(unsigned.calculate_with_bit_errors(0.0, 1.0), 0b1010_1100);
+                assert_eq!(signed.calculate_with_bit_errors(0.0, 1.0), -0b0101_0100);
+            }
+        });
+
+        // Roughly the requested fraction of bits are flipped
+        let flipped: u32 = generators::with_seed(258, || {
+            (0..1000)
+                .map(|_| (unsigned.calculate_with_bit_errors(0.0, 0.25) ^ 0b0101_0011).count_ones())
+                .sum()
+        });
+        assert!((1800..2200).contains(&flipped), "{flipped}");
+    }
+
+    #[test]
+    fn rolling_rms_test() {
+        use super::*;
+
+        let sine = generators::test_sine(1000.0, 1.0);
+        let steady = 1000.0 / 2_f64.sqrt();
+
+        let trace = sine.rolling_rms(0.0, 5.0, 0.01, 1.0);
+        assert_eq!(trace.len(), 500);
+        for &(time, rms) in &trace[100..] {
+            assert!((rms - steady).abs() < 5.0, "{rms} at {time}");
+        }
+
+        // A window longer than the range still has a value everywhere
+        let trace = sine.rolling_rms(0.0, 1.0, 0.01, 10.0);
+        assert_eq!(trace.len(), 100);
+        assert!((trace[99].1 - steady).abs() < 5.0);
+    }
+
+    #[cfg(feature = "spectral")]
+    #[test]
+    fn spectral_centroid_test() {
+        use super::*;
+
+        let sine = |period: f64| generators::test_sine(1000.0, period);
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        // 2 Hz and 20 Hz, sampled at 100 Hz for a second
+        let low = sine(0.5).spectral_centroid(0.0, 1.0, 0.01);
+        let high = sine(0.05).spectral_centroid(0.0, 1.0, 0.01);
+        assert!(high > low);
+
+        // Without the noise spread across every bin, each is exactly at its frequency
+        let deterministic = generators::Deterministic::enable();
+        let low = sine(0.5).spectral_centroid(0.0, 1.0, 0.01);
+        let high = sine(0.05).spectral_centroid(0.0, 1.0, 0.01);
+        drop(deterministic);
+        assert!((low - 2.0).abs() < 1e-6, "{low}");
+        assert!((high - 20.0).abs() < 1e-6, "{high}");
+    }
+
+    #[cfg(feature = "spectral")]
+    #[test]
+    fn spectral_peaks_test() {
+        use super::*;
+
+        let two_tone = generators::TwoTone {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            freq1: 50.0,
+            freq2: 120.0,
+            amplitude1: 1000.0,
+            amplitude2: 500.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let deterministic = generators::Deterministic::enable();
+        // One second at 1 kHz, so each bin is 1 Hz
+        let peaks = two_tone.spectral_peaks(0.0, 1.0, 0.001, 2);
+        drop(deterministic);
+
+        assert_eq!(peaks.len(), 2);
+        assert!((peaks[0].0 - 50.0).abs() < 1e-9, "{peaks:?}");
+        assert!((peaks[1].0 - 120.0).abs() < 1e-9, "{peaks:?}");
+        assert!((peaks[0].1 - 1000.0).abs() < 1.0, "{peaks:?}");
+        assert!((peaks[1].1 - 500.0).abs() < 1.0, "{peaks:?}");
+    }
+
+    #[test]
+    fn ramp_test() {
+        use super::*;
+
+        let ramp = generators::Ramp {
+            minimum: -100.0,
+            maximum: 300.0,
+            ramp_duration: 2.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+
+        assert_eq!(ramp.calculate_noiseless(0.0), -100.0);
+        assert_eq!(ramp.calculate_noiseless(0.5), 0.0);
+        assert_eq!(ramp.calculate_noiseless(1.0), 100.0);
+        for time in [2.0, 2.5, 10.0, 1000.0] {
+            assert_eq!(ramp.calculate_noiseless(time), 300.0);
+        }
+        assert_eq!(ramp.calculate_noiseless(-1.0), -100.0);
+        assert_eq!(ramp.max_slew_rate(), 200.0);
+
+        // It reports itself as a sawtooth, but doesn't repeat like one
+        assert_eq!(ramp.time_to_next_period(1.0), f64::INFINITY);
+        assert!(ramp.period_mean().is_nan());
+        assert_eq!(ramp.waveform_spec(), None);
+        assert_eq!(ramp.encode_config(), None);
+        assert_eq!(generators::phase_difference(&ramp, &ramp), None);
+    }
+
+    #[test]
+    fn interleave_test() {
+        use super::*;
+
+        // Callbacks have no noise, so each is exactly its constant
+        let constant = |value: f64| {
+            generators::Callback::new(move |_| value, -1000.0, 1000.0, 16, true, 1.0, 0.0)
+        };
+        let (a, b, c) = (constant(1.0), constant(-20.0), constant(300.0));
+
+        let stream = generators::interleave(&[&a, &b, &c], 0.0, 1.0, 0.25);
+        assert_eq!(stream, [1, -20, 300].repeat(4));
+        assert!(generators::interleave(&[], 0.0, 1.0, 0.25).is_empty());
+    }
+
+    #[test]
+    fn time_to_next_period_test() {
+        use super::*;
+
+        let sine = |period: f64, phase: f64| generators::Sine {
+            phase,
+            ..generators::test_sine(1000.0, period)
+        };
+
+        assert!((sine(1.0, 0.0).time_to_next_period(0.3) - 0.7).abs() < 1e-12);
+        assert!((sine(1.0, 0.0).time_to_next_period(5.3) - 0.7).abs() < 1e-12);
+        assert!((sine(1.0, 0.0).time_to_next_period(-0.3) - 0.3).abs() < 1e-12);
+        assert!((sine(1.0, 0.0).time_to_next_period(2.0) - 1.0).abs() < 1e-12);
+        assert!((sine(2.0, 0.5).time_to_next_period(0.3) - 1.2).abs() < 1e-12);
+        assert_eq!(sine(0.0, 0.0).time_to_next_period(0.3), f64::INFINITY);
+    }
+
+    #[test]
+    fn frequency_hop_test() {
+        use super::*;
+
+        let frequencies = vec![10.0, 25.0, 40.0];
+        let hop = generators::FrequencyHop {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            frequencies: frequencies.clone(),
+            hop_period: 0.2,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+
+        // Each 0.2 s hop holds a whole number of cycles of every frequency, sampled at 1 kHz
+        for interval in 0..6 {
+            let start = interval as f64 * 0.2;
+            let samples: Vec<f64> = (0..200)
+                .map(|i| hop.calculate_float(start + i as f64 / 1000.0))
+                .collect();
+            // Each bin is 5 Hz
+            let spectrum = crate::dft::power_spectrum(&samples);
+            let power = |frequency: f64| spectrum[(frequency / 5.0) as usize];
+
+            let scheduled = frequencies[interval % 3];
+            assert_eq!(hop.frequency_at(start + 0.1), Some(scheduled));
+            let dominant = frequencies
+                .iter()
+                .copied()
+                .max_by(|&a, &b| power(a).total_cmp(&power(b)))
+                .unwrap();
+            assert_eq!(dominant, scheduled, "hop {interval}");
+        }
+
+        // Phase continuous, so no sample-to-sample jump is bigger than the fastest slope allows
+        let max_step = hop.max_slew_rate() * 0.001 * 1.01;
+        let values: Vec<f64> = (0..1200)
+            .map(|i| hop.calculate_noiseless(i as f64 / 1000.0))
+            .collect();
+        assert!(values
+            .windows(2)
+            .all(|pair| (pair[1] - pair[0]).abs() <= max_step));
+
+        // It reports itself as a sine, but its period isn't the hop period
+        assert_eq!(hop.time_to_next_period(0.1), f64::INFINITY);
+        assert!(hop.period_jitter_std(0.0, 1.2, 0.001).is_nan());
+        assert_eq!(hop.waveform_spec(), None);
+        assert_eq!(generators::phase_difference(&hop, &hop), None);
+    }
+
+    #[test]
+    fn signal_code_test() {
+        use super::*;
+
+        for signal_type in SignalType::get_types() {
+            assert_eq!(
+                SignalType::from_code(signal_type.to_code()),
+                Some(signal_type)
+            );
+        }
+        assert_eq!(SignalType::Constant.to_code(), 4);
+        assert_eq!(SignalType::from_code(5), None);
+    }
+
+    #[test]
+    fn two_tone_test() {
+        use super::*;
+
+        let two_tone = generators::TwoTone {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            freq1: 50.0,
+            freq2: 55.0,
+            amplitude1: 1000.0,
+            amplitude2: 500.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+
+        // One second at 1 kHz, so each bin is 1 Hz
+        let samples: Vec<f64> = (0..1000)
+            .map(|i| two_tone.calculate_float(i as f64 / 1000.0))
+            .collect();
+        let spectrum = crate::dft::power_spectrum(&samples);
+        let magnitude = |bin: usize| spectrum[bin].sqrt();
+
+        let mut bins: Vec<(usize, f64)> = (1..500).map(|bin| (bin, magnitude(bin))).collect();
+        bins.sort_by(|a, b| b.1.total_cmp(&a.1));
+        assert_eq!((bins[0].0, bins[1].0), (50, 55));
+        assert!(bins[2].1 < bins[1].1 / 10.0);
+
+        // It reports itself as a sine, but isn't one sine to describe or compare the phase of
+        assert_eq!(two_tone.get_type(), SignalType::Sine);
+        assert_eq!(two_tone.waveform(), None);
+        assert_eq!(two_tone.waveform_spec(), None);
+        assert_eq!(two_tone.encode_config(), None);
+        assert_eq!(generators::phase_difference(&two_tone, &two_tone), None);
+    }
+
+    #[test]
+    fn harmonic_series_test() {
+        use super::*;
+
+        let series = |rolloff_db_per_octave| generators::HarmonicSeries {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            period: 0.02,
+            phase: 0.003,
+            num_harmonics: 4,
+            rolloff_db_per_octave,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+
+        for rolloff in [0.0, 6.0, 12.0] {
+            let series = series(rolloff);
+
+            // One second at 1 kHz, so each bin is 1 Hz and the harmonics are every 50 bins
+            let samples: Vec<f64> = (0..1000)
+                .map(|i| series.calculate_noiseless(i as f64 / 1000.0))
+                .collect();
+            let spectrum = crate::dft::power_spectrum(&samples);
+            let magnitude = |bin: usize| spectrum[bin].sqrt();
+
+            let fundamental = magnitude(50);
+            for multiple in 2..=5 {
+                let db = 20.0 * (magnitude(50 * multiple) / fundamental).log10();
+                let expected = -rolloff * (multiple as f64).log2();
+                assert!((db - expected).abs() < 0.01, "{rolloff}: {multiple}: {db}");
+            }
+
+            // Nothing past the last harmonic
+            assert!(magnitude(300) < fundamental * 1e-6);
+        }
+
+        assert!((series(6.0).harmonic_amplitude(2) - 501.19).abs() < 0.01);
+        assert!((series(0.0).get_amplitude() - 5000.0).abs() < 1e-9);
+
+        // It reports itself as a sine, but the harmonics aren't part of a sine's spec or config
+        let series = series(6.0);
+        assert_eq!(series.get_type(), SignalType::Sine);
+        assert_eq!(series.waveform(), None);
+        assert_eq!(series.waveform_spec(), None);
+        assert_eq!(series.encode_config(), None);
+        assert_eq!(generators::phase_difference(&series, &series), None);
+    }
+
+    #[test]
+    fn band_noise_test() {
+        use super::*;
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let params = generators::SignalParams {
+            minimum: -30000.0,
+            maximum: 30000.0,
+            amplitude: 1000.0,
+            period: 0.0,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        let noise = generators::BandNoise::new(&params, 100.0, 200.0, 0.001, 299);
+
+        // Two seconds at 1 kHz once the filter has settled, so each bin is 0.5 Hz
+        let samples: Vec<f64> = (0..2500)
+            .map(|i| noise.calculate_float(i as f64 / 1000.0))
+            .skip(500)
+            .collect();
+        let spectrum = crate::dft::power_spectrum(&samples);
+        let total: f64 = spectrum.iter().sum();
+        let between = |low: f64, high: f64| {
+            spectrum[(low * 2.0) as usize..(high * 2.0) as usize]
+                .iter()
+                .sum::<f64>()
+                / total
+        };
+
+        // Most of the power is in the band, and what leaks out is in the filters' skirts, as an
+        // octave outside the band is down by 24 dB
+        assert!(between(100.0, 200.0) > 0.75, "{}", between(100.0, 200.0));
+        assert!(between(70.0, 285.0) > 0.95, "{}", between(70.0, 285.0));
+        assert!(between(0.0, 50.0) < 0.005, "{}", between(0.0, 50.0));
+        assert!(between(400.0, 500.0) < 0.005, "{}", between(400.0, 500.0));
+
+        let rms = (samples.iter().map(|x| x * x).sum::<f64>() / samples.len() as f64).sqrt();
+        assert!((rms - 1000.0).abs() < 200.0, "{rms}");
+        assert_eq!(noise.noise_variance(), 1000.0 * 1000.0);
+
+        // The same seed gives the same noise
+        let again = generators::BandNoise::new(&params, 100.0, 200.0, 0.001, 299);
+        let repeated: Vec<f64> = (0..2500)
+            .map(|i| again.calculate_float(i as f64 / 1000.0))
+            .skip(500)
+            .collect();
+        assert_eq!(repeated, samples);
+
+        // It is all noise, around a mean of 0
+        assert_eq!(noise.calculate_noiseless(1.0), 0.0);
+        assert_eq!(noise.period_mean(), 0.0);
+        assert_eq!(noise.waveform_spec(), None);
+        assert_eq!(noise.recommended_bits(0.0), 13);
+        let slope = samples
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs() * 1000.0)
+            .fold(0.0, f64::max);
+        assert!(slope < noise.max_slew_rate(), "{slope}");
+        assert!(noise.noise_free_bits(0.0, 2.0, 0.001) < 16.0);
+
+        let deterministic = generators::Deterministic::enable();
+        let quiet = noise.calculate_float(2.5);
+        let variance = noise.noise_variance();
+        drop(deterministic);
+        assert_eq!((quiet, variance), (0.0, 0.0));
+    }
+
+    #[test]
+    fn autocorrelation_test() {
+        use super::*;
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let sine = generators::test_sine(1000.0, 1.0);
+        // A period is 100 samples, so the correlation peaks again at lag 100
+        let correlation = sine.autocorrelation(0.0, 10.0, 0.01, 150);
+        assert!((correlation[0] - 1.0).abs() < 1e-12);
+        let peak = (50..=150)
+            .max_by(|&a, &b| correlation[a].total_cmp(&correlation[b]))
+            .unwrap();
+        assert_eq!(peak, 100);
+
+        // All that varies in a constant is its white noise, which is uncorrelated with itself
+        let constant = generators::test_constant(1000.0);
+        let correlation = constant.autocorrelation(0.0, 100.0, 0.01, 10);
+        assert!(
+            correlation[1..].iter().all(|r| r.abs() < 0.1),
+            "{correlation:?}"
+        );
+    }
+
+    #[test]
+    fn encode_config_test() {
+        use super::*;
+
+        for signal_type in SignalType::get_types() {
+            let params = generators::SignalParams {
+                minimum: -123.25,
+                maximum: 456.5,
+                amplitude: 100.0 / 3.0,
+                period: 0.1,
+                phase: -0.7,
+                num_bits: 12,
+                is_signed: signal_type == SignalType::Square,
+                scale: 0.01,
+                offset: 7.0,
+            };
+            let signal = generators::from_params(signal_type, &params);
+
+            let encoded = signal.encode_config().unwrap();
+            assert_eq!(encoded.len(), generators::CONFIG_ENCODED_LEN);
+            let decoded = generators::decode_config(&encoded).unwrap();
+            assert_eq!(decoded.to_params(), Some((signal_type, params)));
+            assert_eq!(decoded.encode_config(), Some(encoded));
+        }
+
+        // Wrappers and the other generators can't be rebuilt from the fixed fields
+        let sine = generators::from_params(
+            SignalType::Sine,
+            &generators::SignalParams {
+                minimum: get_min_limit(),
+                maximum: get_max_limit(),
+                amplitude: 1000.0,
+                period: 1.0,
+                phase: 0.0,
+                num_bits: 16,
+                is_signed: true,
+                scale: 1.0,
+                offset: 0.0,
+            },
+        );
+        let blocked = crate::signal_wrapper::DcBlocker::new(sine, 0.1);
+        assert_eq!(blocked.encode_config(), None);
+        let ramp =
+            generators::Callback::new(|time| time * 100.0, -1000.0, 1000.0, 16, true, 1.0, 0.0);
+        assert_eq!(ramp.encode_config(), None);
+
+        assert_eq!(
+            generators::decode_config(&[0; 3]).unwrap_err(),
+            generators::ConfigDecodeError::WrongLength(3)
+        );
+        let mut unknown = vec![0; generators::CONFIG_ENCODED_LEN];
+        unknown[0] = 200;
+        assert_eq!(
+            generators::decode_config(&unknown).unwrap_err(),
+            generators::ConfigDecodeError::UnknownType(200)
+        );
     }
 }
@@ -0,0 +1,1968 @@
+use std::cell::{Cell, RefCell};
+use std::f64::consts::PI;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::signal_type::generators::{
+    hash_unit, is_deterministic, with_rng, with_seed, Signal, SignalParams, SignalWarning,
+};
+use crate::signal_type::SignalType;
+
+/// A macro to forward the configuration getters of a wrapper to its `inner` signal
+///
+/// The descriptions a generator may override, its recommended bits, slew rate and period
+/// boundaries, are forwarded too, so they aren't lost to the defaults once it is wrapped.
+///
+/// Wrappers that change the size of the waveform pass `custom_peak` and implement
+/// `effective_peak` themselves, and those that move its mean pass `custom_mean` and implement
+/// `period_mean`
+macro_rules! signal_wrapper_getters {
+    () => {
+        signal_wrapper_getters!(custom_mean);
+        fn period_mean(&self) -> f64 {
+            self.inner.period_mean()
+        }
+    };
+    (custom_peak) => {
+        signal_wrapper_getters!(custom_peak, custom_mean);
+        fn period_mean(&self) -> f64 {
+            self.inner.period_mean()
+        }
+    };
+    (custom_mean) => {
+        signal_wrapper_getters!(custom_peak, custom_mean);
+        fn effective_peak(&self) -> f64 {
+            self.inner.effective_peak()
+        }
+    };
+    (custom_peak, custom_mean) => {
+        fn get_type(&self) -> SignalType {
+            self.inner.get_type()
+        }
+        fn waveform(&self) -> Option<SignalType> {
+            self.inner.waveform()
+        }
+        fn validate(&self) -> Vec<SignalWarning> {
+            self.inner.validate()
+        }
+        fn get_minimum(&self) -> f64 {
+            self.inner.get_minimum()
+        }
+        fn get_maximum(&self) -> f64 {
+            self.inner.get_maximum()
+        }
+        fn get_amplitude(&self) -> f64 {
+            self.inner.get_amplitude()
+        }
+        fn get_period(&self) -> f64 {
+            self.inner.get_period()
+        }
+        fn get_phase(&self) -> f64 {
+            self.inner.get_phase()
+        }
+        fn get_num_bits(&self) -> u8 {
+            self.inner.get_num_bits()
+        }
+        fn is_signed(&self) -> bool {
+            self.inner.is_signed()
+        }
+        fn get_scale(&self) -> f64 {
+            self.inner.get_scale()
+        }
+        fn get_offset(&self) -> f64 {
+            self.inner.get_offset()
+        }
+        fn recommended_bits(&self, headroom_fraction: f64) -> u8 {
+            self.inner.recommended_bits(headroom_fraction)
+        }
+        fn max_slew_rate(&self) -> f64 {
+            self.inner.max_slew_rate()
+        }
+        fn time_to_next_period(&self, time: f64) -> f64 {
+            self.inner.time_to_next_period(time)
+        }
+    };
+}
+
+/// A one-pole high-pass filter that removes the DC component of a signal,
+/// emulating an AC-coupled channel
+///
+/// The filter is stateful, so the signal should be calculated at increasing times
+#[derive(Debug)]
+pub struct DcBlocker {
+    pub inner: Box<dyn Signal>,
+    /// The -3 dB cutoff frequency in Hz
+    pub cutoff: f64,
+    /// The time, input and output of the last calculation
+    state: Cell<Option<(f64, f64, f64)>>,
+    /// The same as `state`, for the noiseless calculations
+    noiseless_state: Cell<Option<(f64, f64, f64)>>,
+}
+
+impl DcBlocker {
+    pub fn new(inner: Box<dyn Signal>, cutoff: f64) -> Self {
+        DcBlocker {
+            inner,
+            cutoff,
+            state: Cell::new(None),
+            noiseless_state: Cell::new(None),
+        }
+    }
+
+    /// The delay the filter introduces at the inner signal's fundamental frequency, in seconds
+    ///
+    /// A constant is at DC, where the delay is the full time constant. NaN for a signal that isn't
+    /// a plain waveform and has no period, whose fundamental isn't known.
+    pub fn group_delay(&self) -> f64 {
+        let rc = 1.0 / (2.0 * PI * self.cutoff);
+        let period = self.inner.get_period();
+        let omega = match self.inner.waveform() {
+            Some(SignalType::Constant) => 0.0,
+            _ if period > 0.0 && period.is_finite() => 2.0 * PI / period,
+            Some(_) => 0.0,
+            None => return f64::NAN,
+        };
+        rc / (1.0 + (omega * rc).powi(2))
+    }
+
+    /// Filter the next input, advancing the given state
+    fn step(&self, state: &Cell<Option<(f64, f64, f64)>>, time: f64, input: f64) -> f64 {
+        let output = match state.get() {
+            Some((last_time, last_input, last_output)) => {
+                let rc = 1.0 / (2.0 * PI * self.cutoff);
+                let alpha = rc / (rc + (time - last_time));
+                alpha * (last_output + input - last_input)
+            }
+            // The coupling capacitor starts uncharged, so the first sample passes straight through
+            None => input,
+        };
+        state.set(Some((time, input, output)));
+        output
+    }
+}
+
+impl Signal for DcBlocker {
+    signal_wrapper_getters!(custom_mean);
+
+    /// Once the coupling capacitor has charged, none of the DC gets through
+    fn period_mean(&self) -> f64 {
+        0.0
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        let input = self.inner.calculate_noiseless(time);
+        self.step(&self.noiseless_state, time, input)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        let input = self.inner.calculate_float(time);
+        self.step(&self.state, time, input)
+    }
+}
+
+/// Models a sensor that reads low for a while after it has been driven past its maximum
+///
+/// Once the inner signal drops back below `maximum`, the output is depressed by the largest
+/// overload seen while clipping, recovering linearly to the true value over `recovery_time`
+/// seconds. The wrapper is stateful, so the signal should be calculated at increasing times.
+#[derive(Debug)]
+pub struct SaturationRecovery {
+    pub inner: Box<dyn Signal>,
+    /// How long the output takes to recover after clipping, in seconds
+    pub recovery_time: f64,
+    /// The time clipping last ended and the overload to recover from
+    state: Cell<Option<(f64, f64)>>,
+    /// The same as `state`, for the noiseless calculations
+    noiseless_state: Cell<Option<(f64, f64)>>,
+}
+
+impl SaturationRecovery {
+    pub fn new(inner: Box<dyn Signal>, recovery_time: f64) -> Self {
+        SaturationRecovery {
+            inner,
+            recovery_time,
+            state: Cell::new(None),
+            noiseless_state: Cell::new(None),
+        }
+    }
+
+    /// Apply any pending recovery to the next value, advancing the given state
+    fn step(&self, state: &Cell<Option<(f64, f64)>>, time: f64, value: f64) -> f64 {
+        let overload = value - self.get_maximum();
+
+        let recovering = state
+            .get()
+            .filter(|(clip_time, _)| time - clip_time < self.recovery_time);
+
+        if overload > 0.0 {
+            // Still clipping, so keep track of the worst overload in this episode
+            let worst = recovering.map_or(overload, |(_, worst)| worst.max(overload));
+            state.set(Some((time, worst)));
+            return value;
+        }
+
+        match recovering {
+            Some((clip_time, worst)) => {
+                let remaining = 1.0 - (time - clip_time) / self.recovery_time;
+                value - worst * remaining
+            }
+            None => value,
+        }
+    }
+}
+
+impl Signal for SaturationRecovery {
+    signal_wrapper_getters!();
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        let value = self.inner.calculate_noiseless(time);
+        self.step(&self.noiseless_state, time, value)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        let value = self.inner.calculate_float(time);
+        self.step(&self.state, time, value)
+    }
+}
+
+/// The probability distribution of the dither added by `Dithered`
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum DitherKind {
+    /// Rectangular (RPDF), uniform within ±½ LSB
+    Rectangular,
+    /// Triangular (TPDF), the sum of two rectangular dithers, within ±1 LSB
+    Triangular,
+}
+
+/// Adds dither of up to a few LSBs to a signal before it is quantized by `shrink_to_fit`, so the
+/// quantization error is spread into noise rather than following the signal
+///
+/// Triangular dither also makes the error's power independent of the signal, which rectangular
+/// dither does not. Like the built-in noise, this is disabled while deterministic mode is enabled.
+#[derive(Debug)]
+pub struct Dithered {
+    pub inner: Box<dyn Signal>,
+    pub dither_kind: DitherKind,
+}
+
+impl Dithered {
+    pub fn new(inner: Box<dyn Signal>, dither_kind: DitherKind) -> Self {
+        Dithered { inner, dither_kind }
+    }
+
+    /// A single sample of the dither, in LSBs
+    fn dither(&self) -> f64 {
+        if is_deterministic() {
+            return 0.0;
+        }
+
+        with_rng(|rng| match self.dither_kind {
+            DitherKind::Rectangular => rng.gen_range(-0.5..0.5),
+            DitherKind::Triangular => rng.gen_range(-0.5..0.5) + rng.gen_range(-0.5..0.5),
+        })
+    }
+}
+
+impl Signal for Dithered {
+    signal_wrapper_getters!();
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(time)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.inner.calculate_float(time) + self.dither() * self.verify_lsb()
+    }
+
+    fn noise_variance(&self) -> f64 {
+        if is_deterministic() {
+            return 0.0;
+        }
+
+        // Each rectangular dither of one LSB has a variance of LSB²/12
+        let lsb = self.verify_lsb();
+        let dither = match self.dither_kind {
+            DitherKind::Rectangular => lsb * lsb / 12.0,
+            DitherKind::Triangular => lsb * lsb / 6.0,
+        };
+        self.inner.noise_variance() + dither
+    }
+}
+
+/// Replaces a square or sawtooth wave with its additive synthesis from only the harmonics
+/// below the Nyquist frequency, so it doesn't alias when sampled at `sample_rate`
+///
+/// Other waveforms are already band-limited and pass through unchanged
+#[derive(Debug)]
+pub struct BandLimited {
+    pub inner: Box<dyn Signal>,
+    /// The rate the signal will be sampled at, in Hz
+    pub sample_rate: f64,
+}
+
+impl BandLimited {
+    /// The most harmonics summed for one value, so a fast sample rate or long period can't stall
+    /// it. The harmonics past this are all under a ten-thousandth of the fundamental.
+    pub const MAX_HARMONICS: usize = 10_000;
+
+    pub fn new(inner: Box<dyn Signal>, sample_rate: f64) -> Self {
+        BandLimited { inner, sample_rate }
+    }
+
+    /// Sum `sin(2πk(t + phase) / period) / k` over the harmonics `k` below Nyquist
+    fn harmonic_sum(&self, time: f64, odd_only: bool) -> f64 {
+        let period = self.get_period();
+        let highest = ((self.sample_rate / 2.0 * period).ceil() as usize).min(Self::MAX_HARMONICS);
+        let angle = 2.0 * PI * (time + self.get_phase()) / period;
+
+        (1..highest)
+            .filter(|k| !odd_only || k % 2 == 1)
+            .map(|k| (k as f64 * angle).sin() / k as f64)
+            .sum()
+    }
+}
+
+impl Signal for BandLimited {
+    signal_wrapper_getters!();
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        // Without a cycle there are no harmonics to sum
+        let period = self.get_period();
+        if !(period > 0.0 && period.is_finite()) {
+            return self.inner.calculate_noiseless(time);
+        }
+
+        let amplitude = self.get_amplitude();
+        match self.waveform() {
+            Some(SignalType::Square) => 4.0 * amplitude / PI * self.harmonic_sum(time, true),
+            Some(SignalType::Sawtooth) => -2.0 * amplitude / PI * self.harmonic_sum(time, false),
+            _ => self.inner.calculate_noiseless(time),
+        }
+    }
+}
+
+/// Fades a signal in from zero over its first `soft_start` seconds, so periodic signals
+/// don't start with a step at t=0
+#[derive(Debug)]
+pub struct SoftStart {
+    pub inner: Box<dyn Signal>,
+    /// How long the fade in lasts, in seconds
+    pub soft_start: f64,
+}
+
+impl SoftStart {
+    pub fn new(inner: Box<dyn Signal>, soft_start: f64) -> Self {
+        SoftStart { inner, soft_start }
+    }
+
+    /// The smoothstep envelope, rising from 0 at t=0 to 1 at `soft_start` with zero slope at both ends
+    pub fn envelope(&self, time: f64) -> f64 {
+        if time >= self.soft_start {
+            return 1.0;
+        }
+        let x = (time / self.soft_start).max(0.0);
+        x * x * (3.0 - 2.0 * x)
+    }
+}
+
+impl Signal for SoftStart {
+    signal_wrapper_getters!();
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(time) * self.envelope(time)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.inner.calculate_float(time) * self.envelope(time)
+    }
+}
+
+/// Limits the shape of a signal's waveform to `lower` to `upper`, separately from the
+/// physical rails of the sensor
+///
+/// This is the first of two clamp stages: the waveform (noise included) is clipped to these
+/// bounds, then `calculate` clamps the result to the signal's `minimum` and `maximum` rails.
+/// Whichever range is tighter governs.
+#[derive(Debug)]
+pub struct WaveformBounds {
+    pub inner: Box<dyn Signal>,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl WaveformBounds {
+    pub fn new(inner: Box<dyn Signal>, lower: f64, upper: f64) -> Self {
+        WaveformBounds {
+            inner,
+            lower,
+            upper,
+        }
+    }
+
+    /// Clip a value to the waveform bounds, without panicking if they are inverted
+    fn clip(&self, value: f64) -> f64 {
+        value.max(self.lower).min(self.upper)
+    }
+}
+
+impl Signal for WaveformBounds {
+    signal_wrapper_getters!(custom_peak, custom_mean);
+
+    fn effective_peak(&self) -> f64 {
+        let bound = self.lower.abs().max(self.upper.abs());
+        self.inner.effective_peak().min(bound)
+    }
+
+    /// Clipping moves the mean by however much of the waveform is cut off
+    fn period_mean(&self) -> f64 {
+        f64::NAN
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.clip(self.inner.calculate_noiseless(time))
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.clip(self.inner.calculate_float(time))
+    }
+}
+
+/// Only updates a signal on a grid of times, holding the value in between, like a
+/// sample-and-hold DAC
+#[derive(Debug)]
+pub struct TimeQuantized {
+    pub inner: Box<dyn Signal>,
+    /// The spacing of the grid in seconds, where 0 or less disables the quantization
+    pub grid: f64,
+}
+
+impl TimeQuantized {
+    pub fn new(inner: Box<dyn Signal>, grid: f64) -> Self {
+        TimeQuantized { inner, grid }
+    }
+
+    /// The last grid point at or before `time`
+    pub fn quantize(&self, time: f64) -> f64 {
+        if self.grid > 0.0 {
+            (time / self.grid).floor() * self.grid
+        } else {
+            time
+        }
+    }
+}
+
+impl Signal for TimeQuantized {
+    signal_wrapper_getters!();
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(self.quantize(time))
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.inner.calculate_float(self.quantize(time))
+    }
+}
+
+/// Adds mains hum to a signal, a sine at the mains frequency whose amplitude is a fraction of
+/// the signal's
+#[derive(Debug)]
+pub struct MainsHum {
+    pub inner: Box<dyn Signal>,
+    /// The mains frequency in Hz, usually 50 or 60
+    pub frequency: f64,
+    /// The hum amplitude as a fraction of the amplitude
+    pub level: f64,
+}
+
+impl MainsHum {
+    pub fn new(inner: Box<dyn Signal>, frequency: f64, level: f64) -> Self {
+        MainsHum {
+            inner,
+            frequency,
+            level,
+        }
+    }
+
+    /// The hum on its own at a given time
+    pub fn hum(&self, time: f64) -> f64 {
+        self.level * self.inner.get_amplitude() * (2.0 * PI * self.frequency * time).sin()
+    }
+}
+
+impl Signal for MainsHum {
+    signal_wrapper_getters!(custom_peak);
+
+    fn effective_peak(&self) -> f64 {
+        self.inner.effective_peak() + self.level.abs() * self.inner.get_amplitude().abs()
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(time) + self.hum(time)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.inner.calculate_float(time) + self.hum(time)
+    }
+}
+
+/// Scales a signal by a temperature-dependent gain, `1 + tempco * (temperature - ref_temp)`,
+/// to model a sensor's temperature coefficient
+#[derive(Debug)]
+pub struct TempDrift {
+    pub inner: Box<dyn Signal>,
+    /// The temperature over time, in degrees
+    pub temperature: Box<dyn Signal>,
+    /// The fractional change in gain per degree
+    pub tempco: f64,
+    /// The temperature at which the gain is exactly 1
+    pub ref_temp: f64,
+}
+
+impl TempDrift {
+    pub fn new(
+        inner: Box<dyn Signal>,
+        temperature: Box<dyn Signal>,
+        tempco: f64,
+        ref_temp: f64,
+    ) -> Self {
+        TempDrift {
+            inner,
+            temperature,
+            tempco,
+            ref_temp,
+        }
+    }
+
+    /// The gain at a given time, from the noiseless temperature
+    pub fn gain(&self, time: f64) -> f64 {
+        1.0 + self.tempco * (self.temperature.calculate_noiseless(time) - self.ref_temp)
+    }
+}
+
+impl Signal for TempDrift {
+    signal_wrapper_getters!(custom_peak, custom_mean);
+
+    /// The gain follows the temperature, which may rise and fall with the signal
+    fn period_mean(&self) -> f64 {
+        f64::NAN
+    }
+
+    fn effective_peak(&self) -> f64 {
+        // The gain is furthest from 1 when the temperature is furthest from the reference
+        let swing = self.temperature.effective_peak() + self.ref_temp.abs();
+        self.inner.effective_peak() * (1.0 + self.tempco.abs() * swing)
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(time) * self.gain(time)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.inner.calculate_float(time) * self.gain(time)
+    }
+}
+
+/// The difference between two channels, `a - b`, as a signal of its own
+///
+/// The difference is stored with a template's configuration, so its bits, scale, offset and
+/// limits are independent of the channels'. Its noise is whatever the channels' noise leaves.
+#[derive(Debug)]
+pub struct Difference {
+    pub a: Box<dyn Signal>,
+    pub b: Box<dyn Signal>,
+    pub signal_type: SignalType,
+    pub config: SignalParams,
+}
+
+/// Build the difference `a - b`, configured like `template`
+pub fn difference(
+    a: Box<dyn Signal>,
+    b: Box<dyn Signal>,
+    template: &dyn Signal,
+) -> Box<dyn Signal> {
+    Box::new(Difference {
+        a,
+        b,
+        signal_type: template.get_type(),
+        config: SignalParams {
+            minimum: template.get_minimum(),
+            maximum: template.get_maximum(),
+            amplitude: template.get_amplitude(),
+            period: template.get_period(),
+            phase: template.get_phase(),
+            num_bits: template.get_num_bits(),
+            is_signed: template.is_signed(),
+            scale: template.get_scale(),
+            offset: template.get_offset(),
+        },
+    })
+}
+
+impl Signal for Difference {
+    fn get_type(&self) -> SignalType {
+        self.signal_type
+    }
+    fn get_minimum(&self) -> f64 {
+        self.config.minimum
+    }
+    fn get_maximum(&self) -> f64 {
+        self.config.maximum
+    }
+    fn get_amplitude(&self) -> f64 {
+        self.config.amplitude
+    }
+    fn get_period(&self) -> f64 {
+        self.config.period
+    }
+    fn get_phase(&self) -> f64 {
+        self.config.phase
+    }
+    fn get_num_bits(&self) -> u8 {
+        self.config.num_bits
+    }
+    fn is_signed(&self) -> bool {
+        self.config.is_signed
+    }
+    fn get_scale(&self) -> f64 {
+        self.config.scale
+    }
+    fn get_offset(&self) -> f64 {
+        self.config.offset
+    }
+
+    fn effective_peak(&self) -> f64 {
+        self.a.effective_peak() + self.b.effective_peak()
+    }
+
+    fn period_mean(&self) -> f64 {
+        self.a.period_mean() - self.b.period_mean()
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.a.calculate_noiseless(time) - self.b.calculate_noiseless(time)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.a.calculate_float(time) - self.b.calculate_float(time)
+    }
+
+    fn noise(&self) -> f64 {
+        0.0
+    }
+
+    fn noise_variance(&self) -> f64 {
+        self.a.noise_variance() + self.b.noise_variance()
+    }
+}
+
+/// Samples a signal `conversion_delay` seconds after it is asked for, like a channel of a
+/// multiplexed ADC that converts its channels one after another on each clock
+#[derive(Debug)]
+pub struct ConversionDelay {
+    pub inner: Box<dyn Signal>,
+    /// How long after the clock this channel is converted, in seconds
+    pub conversion_delay: f64,
+}
+
+impl ConversionDelay {
+    pub fn new(inner: Box<dyn Signal>, conversion_delay: f64) -> Self {
+        ConversionDelay {
+            inner,
+            conversion_delay,
+        }
+    }
+}
+
+impl Signal for ConversionDelay {
+    signal_wrapper_getters!();
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(time + self.conversion_delay)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.inner.calculate_float(time + self.conversion_delay)
+    }
+}
+
+/// Adds timing jitter to a signal, sampling it up to `jitter` seconds either side of the
+/// requested time, uniformly at random
+///
+/// Like the built-in noise, this is disabled while deterministic mode is enabled, and the
+/// noiseless value has no jitter
+#[derive(Debug)]
+pub struct PhaseNoise {
+    pub inner: Box<dyn Signal>,
+    /// The largest timing error, in seconds
+    pub jitter: f64,
+}
+
+impl PhaseNoise {
+    pub fn new(inner: Box<dyn Signal>, jitter: f64) -> Self {
+        PhaseNoise { inner, jitter }
+    }
+
+    /// A single sample of the timing error, in seconds
+    fn timing_error(&self) -> f64 {
+        if is_deterministic() || self.jitter <= 0.0 {
+            return 0.0;
+        }
+        with_rng(|rng| rng.gen_range(-self.jitter..self.jitter))
+    }
+}
+
+impl Signal for PhaseNoise {
+    signal_wrapper_getters!();
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(time)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.inner.calculate_float(time + self.timing_error())
+    }
+}
+
+/// Only passes a signal through while a second gate signal is above `threshold`, like a sensor
+/// that only reports while a relay is on
+///
+/// While the gate is off the output is 0, which `calculate` clamps to the minimum and maximum
+#[derive(Debug)]
+pub struct Gated {
+    pub inner: Box<dyn Signal>,
+    pub gate: Box<dyn Signal>,
+    pub threshold: f64,
+}
+
+impl Gated {
+    pub fn new(inner: Box<dyn Signal>, gate: Box<dyn Signal>, threshold: f64) -> Self {
+        Gated {
+            inner,
+            gate,
+            threshold,
+        }
+    }
+}
+
+impl Signal for Gated {
+    signal_wrapper_getters!(custom_mean);
+
+    /// How much of the signal gets through depends on when the gate is open
+    fn period_mean(&self) -> f64 {
+        f64::NAN
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        if self.gate.calculate_noiseless(time) > self.threshold {
+            self.inner.calculate_noiseless(time)
+        } else {
+            0.0
+        }
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        if self.gate.calculate_float(time) > self.threshold {
+            self.inner.calculate_float(time)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Adds occasional spikes to a signal: each calculation has a `rate` chance of jumping to four
+/// times the signal's peak, in a random direction
+///
+/// The choices are made by hashing the time with `seed`, so they repeat exactly for the same times
+#[derive(Debug)]
+pub struct Spikes {
+    pub inner: Box<dyn Signal>,
+    pub rate: f64,
+    pub seed: u64,
+}
+
+impl Spikes {
+    pub fn new(inner: Box<dyn Signal>, rate: f64, seed: u64) -> Self {
+        Spikes { inner, rate, seed }
+    }
+
+    /// The spike at a given time, or 0 if there is none
+    pub fn spike(&self, time: f64) -> f64 {
+        let chance = hash_unit(self.seed, time);
+        if chance >= self.rate {
+            return 0.0;
+        }
+        // Below half the rate spikes down, above it up
+        let direction = if chance < self.rate / 2.0 { -1.0 } else { 1.0 };
+        direction * 4.0 * self.inner.effective_peak()
+    }
+}
+
+impl Signal for Spikes {
+    signal_wrapper_getters!(custom_peak);
+
+    fn effective_peak(&self) -> f64 {
+        5.0 * self.inner.effective_peak()
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(time) + self.spike(time)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.inner.calculate_float(time) + self.spike(time)
+    }
+}
+
+/// Draws a signal's noise from an RNG seeded by hashing `seed` with the time, so the same time
+/// always gives the same noise
+#[derive(Debug)]
+pub struct Seeded {
+    pub inner: Box<dyn Signal>,
+    pub seed: u64,
+}
+
+impl Seeded {
+    pub fn new(inner: Box<dyn Signal>, seed: u64) -> Self {
+        Seeded { inner, seed }
+    }
+}
+
+impl Signal for Seeded {
+    signal_wrapper_getters!();
+
+    fn noise_variance(&self) -> f64 {
+        self.inner.noise_variance()
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(time)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        with_seed(hash_unit(self.seed, time).to_bits(), || {
+            self.inner.calculate_float(time)
+        })
+    }
+}
+
+/// Drops a signal out to 0 for whole slots of `slot` seconds, each with a `rate` chance
+///
+/// Like `Spikes`, the choices are made by hashing with `seed`, so they repeat exactly
+#[derive(Debug)]
+pub struct Dropouts {
+    pub inner: Box<dyn Signal>,
+    pub rate: f64,
+    /// The length of each slot that may drop out, in seconds
+    pub slot: f64,
+    pub seed: u64,
+}
+
+impl Dropouts {
+    pub fn new(inner: Box<dyn Signal>, rate: f64, slot: f64, seed: u64) -> Self {
+        Dropouts {
+            inner,
+            rate,
+            slot,
+            seed,
+        }
+    }
+
+    /// Whether the signal has dropped out at a given time
+    pub fn is_dropped(&self, time: f64) -> bool {
+        let slot = if self.slot > 0.0 {
+            (time / self.slot).floor()
+        } else {
+            time
+        };
+        hash_unit(self.seed, slot) < self.rate
+    }
+}
+
+impl Signal for Dropouts {
+    signal_wrapper_getters!(custom_mean);
+
+    /// A `rate` share of the slots drop out to 0, on average
+    fn period_mean(&self) -> f64 {
+        self.inner.period_mean() * (1.0 - self.rate.clamp(0.0, 1.0))
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        if self.is_dropped(time) {
+            0.0
+        } else {
+            self.inner.calculate_noiseless(time)
+        }
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        if self.is_dropped(time) {
+            0.0
+        } else {
+            self.inner.calculate_float(time)
+        }
+    }
+}
+
+/// Drops a signal out to 0 in bursts, with a two-state Markov (Gilbert-Elliott) model of a
+/// channel that is either good or bad
+///
+/// At each calculation a good channel turns bad with a `good_to_bad` chance and a bad one recovers
+/// with a `bad_to_good` chance, so bursts last `1 / bad_to_good` samples on average. A dropped
+/// sample reads as 0, so `is_dropped` tells it apart from a real 0. The chances
+/// are drawn from a generator seeded with `seed`, so the same sequence of calculations always
+/// drops the same samples. The model is stateful, so the signal should be calculated at
+/// increasing times.
+#[derive(Debug)]
+pub struct BurstDropouts {
+    pub inner: Box<dyn Signal>,
+    pub good_to_bad: f64,
+    pub bad_to_good: f64,
+    /// Whether the channel was bad at the last calculation, and the generator for the next
+    state: RefCell<(bool, StdRng)>,
+    /// The same as `state`, for the noiseless calculations
+    noiseless_state: RefCell<(bool, StdRng)>,
+}
+
+impl BurstDropouts {
+    pub fn new(inner: Box<dyn Signal>, good_to_bad: f64, bad_to_good: f64, seed: u64) -> Self {
+        BurstDropouts {
+            inner,
+            good_to_bad,
+            bad_to_good,
+            state: RefCell::new((false, StdRng::seed_from_u64(seed))),
+            noiseless_state: RefCell::new((false, StdRng::seed_from_u64(seed))),
+        }
+    }
+
+    /// Whether the last calculation with noise was dropped
+    pub fn is_dropped(&self) -> bool {
+        self.state.borrow().0
+    }
+
+    /// Move the channel on by one calculation, returning whether it is now bad
+    fn step(&self, state: &RefCell<(bool, StdRng)>) -> bool {
+        let (bad, rng) = &mut *state.borrow_mut();
+        let chance: f64 = rng.gen();
+        *bad = match *bad {
+            true => chance >= self.bad_to_good,
+            false => chance < self.good_to_bad,
+        };
+        *bad
+    }
+}
+
+impl Signal for BurstDropouts {
+    signal_wrapper_getters!(custom_mean);
+
+    /// In the long run the channel is bad for `good_to_bad / (good_to_bad + bad_to_good)` of
+    /// the calculations
+    fn period_mean(&self) -> f64 {
+        let good_to_bad = self.good_to_bad.clamp(0.0, 1.0);
+        let bad_to_good = self.bad_to_good.clamp(0.0, 1.0);
+        if good_to_bad + bad_to_good == 0.0 {
+            // The channel never changes from the good state it starts in
+            return self.inner.period_mean();
+        }
+        self.inner.period_mean() * bad_to_good / (good_to_bad + bad_to_good)
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        if self.step(&self.noiseless_state) {
+            0.0
+        } else {
+            self.inner.calculate_noiseless(time)
+        }
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        if self.step(&self.state) {
+            0.0
+        } else {
+            self.inner.calculate_float(time)
+        }
+    }
+}
+
+/// Limits how fast a signal can change to `max_rate` units per second, like an amplifier's
+/// slew rate
+///
+/// The limiter is stateful, so the signal should be calculated at increasing times
+#[derive(Debug)]
+pub struct SlewLimited {
+    pub inner: Box<dyn Signal>,
+    /// The fastest the output can change, in engineering units per second
+    pub max_rate: f64,
+    /// The time and output of the last calculation
+    state: Cell<Option<(f64, f64)>>,
+    /// The same as `state`, for the noiseless calculations
+    noiseless_state: Cell<Option<(f64, f64)>>,
+}
+
+impl SlewLimited {
+    pub fn new(inner: Box<dyn Signal>, max_rate: f64) -> Self {
+        SlewLimited {
+            inner,
+            max_rate,
+            state: Cell::new(None),
+            noiseless_state: Cell::new(None),
+        }
+    }
+
+    /// Move towards the next input as far as the slew rate allows, advancing the given state
+    fn step(&self, state: &Cell<Option<(f64, f64)>>, time: f64, input: f64) -> f64 {
+        let output = match state.get() {
+            Some((last_time, last_output)) => {
+                let max_change = self.max_rate * (time - last_time).abs();
+                last_output + (input - last_output).max(-max_change).min(max_change)
+            }
+            None => input,
+        };
+        state.set(Some((time, output)));
+        output
+    }
+}
+
+impl Signal for SlewLimited {
+    signal_wrapper_getters!(custom_mean);
+
+    /// Limiting a steep edge, like a sawtooth's reset, moves the mean
+    fn period_mean(&self) -> f64 {
+        f64::NAN
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        let input = self.inner.calculate_noiseless(time);
+        self.step(&self.noiseless_state, time, input)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        let input = self.inner.calculate_float(time);
+        self.step(&self.state, time, input)
+    }
+}
+
+/// Maps a signal through a piecewise-linear transfer curve, to model a nonlinear sensor such as a
+/// thermistor
+///
+/// `breakpoints` are `(input, output)` pairs sorted by input. Values between breakpoints are
+/// interpolated and values beyond the ends take the nearest end's output. With no breakpoints
+/// the signal passes through unchanged.
+#[derive(Debug)]
+pub struct Transfer {
+    pub inner: Box<dyn Signal>,
+    pub breakpoints: Vec<(f64, f64)>,
+}
+
+impl Transfer {
+    pub fn new(inner: Box<dyn Signal>, breakpoints: Vec<(f64, f64)>) -> Self {
+        Transfer { inner, breakpoints }
+    }
+
+    /// Map a value through the transfer curve
+    pub fn map(&self, value: f64) -> f64 {
+        let (Some(&(first_in, first_out)), Some(&(last_in, last_out))) =
+            (self.breakpoints.first(), self.breakpoints.last())
+        else {
+            return value;
+        };
+        if value <= first_in {
+            return first_out;
+        }
+        if value >= last_in {
+            return last_out;
+        }
+
+        // The first breakpoint above the value, which can't be the first breakpoint
+        let upper = self
+            .breakpoints
+            .partition_point(|&(input, _)| input <= value);
+        let (x0, y0) = self.breakpoints[upper - 1];
+        let (x1, y1) = self.breakpoints[upper];
+        y0 + (y1 - y0) * (value - x0) / (x1 - x0)
+    }
+}
+
+impl Signal for Transfer {
+    signal_wrapper_getters!(custom_peak, custom_mean);
+
+    /// A curve that isn't a straight line changes the mean in a way that depends on the waveform
+    fn period_mean(&self) -> f64 {
+        match self.breakpoints.is_empty() {
+            true => self.inner.period_mean(),
+            false => f64::NAN,
+        }
+    }
+
+    fn effective_peak(&self) -> f64 {
+        // The curve never goes beyond its breakpoints' outputs
+        match self.breakpoints.is_empty() {
+            true => self.inner.effective_peak(),
+            false => self
+                .breakpoints
+                .iter()
+                .fold(0.0, |peak: f64, &(_, output)| peak.max(output.abs())),
+        }
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.map(self.inner.calculate_noiseless(time))
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.map(self.inner.calculate_float(time))
+    }
+}
+
+/// Adds an ADC's gain and offset errors to the conversion from engineering value to raw count,
+/// for testing calibration routines
+///
+/// The ideal count is multiplied by `1 + gain_error` and then `offset_error` counts are added,
+/// so every output (`calculate`, `calculate_pair`, `calculate_frame`...) reads as the
+/// miscalibrated ADC would, while `count_to_value` keeps the ideal scale and offset
+#[derive(Debug)]
+pub struct AdcError {
+    pub inner: Box<dyn Signal>,
+    /// The fractional error in the gain, e.g. 0.01 reads 1% high
+    pub gain_error: f64,
+    /// The error added to every reading, in counts
+    pub offset_error: f64,
+}
+
+impl AdcError {
+    pub fn new(inner: Box<dyn Signal>, gain_error: f64, offset_error: f64) -> Self {
+        AdcError {
+            inner,
+            gain_error,
+            offset_error,
+        }
+    }
+}
+
+impl Signal for AdcError {
+    signal_wrapper_getters!();
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        self.inner.calculate_noiseless(time)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        self.inner.calculate_float(time)
+    }
+
+    fn value_to_count(&self, value: f64) -> i64 {
+        let ideal = self.inner.value_to_count(value) as f64;
+        let count = (ideal * (1.0 + self.gain_error) + self.offset_error).round() as i64;
+        count.max(self.min_count()).min(self.max_count())
+    }
+}
+
+/// Integrates a signal with an exponential leak, `dy/dt = input - leak_rate * y`, saturating at
+/// the signal's `minimum` and `maximum`, like a leaky bucket counting charge
+///
+/// A constant input settles at `input / leak_rate`. The bucket starts empty at the first
+/// calculation and is stateful, so the signal should be calculated at increasing times.
+#[derive(Debug)]
+pub struct LeakyIntegrator {
+    pub inner: Box<dyn Signal>,
+    /// The fraction of the contents that leaks away per second
+    pub leak_rate: f64,
+    /// The time and output of the last calculation
+    state: Cell<Option<(f64, f64)>>,
+    /// The same as `state`, for the noiseless calculations
+    noiseless_state: Cell<Option<(f64, f64)>>,
+}
+
+impl LeakyIntegrator {
+    pub fn new(inner: Box<dyn Signal>, leak_rate: f64) -> Self {
+        LeakyIntegrator {
+            inner,
+            leak_rate,
+            state: Cell::new(None),
+            noiseless_state: Cell::new(None),
+        }
+    }
+
+    /// Integrate the next input, holding it over the time since the last one, advancing the
+    /// given state
+    fn step(&self, state: &Cell<Option<(f64, f64)>>, time: f64, input: f64) -> f64 {
+        let output = match state.get() {
+            Some((last_time, last_output)) => {
+                let dt = (time - last_time).max(0.0);
+                let integrated = if self.leak_rate > 0.0 {
+                    // The exact solution for an input held constant over the step
+                    let settled = input / self.leak_rate;
+                    settled + (last_output - settled) * (-self.leak_rate * dt).exp()
+                } else {
+                    last_output + input * dt
+                };
+                integrated.max(self.get_minimum()).min(self.get_maximum())
+            }
+            None => 0.0_f64.max(self.get_minimum()).min(self.get_maximum()),
+        };
+        state.set(Some((time, output)));
+        output
+    }
+}
+
+impl Signal for LeakyIntegrator {
+    signal_wrapper_getters!(custom_peak, custom_mean);
+
+    fn effective_peak(&self) -> f64 {
+        self.get_minimum().abs().max(self.get_maximum().abs())
+    }
+
+    /// Once settled, the leak balances the mean input, so the mean is `period_mean / leak_rate`
+    /// (as long as it doesn't saturate). Without a leak it never settles, so this is NaN.
+    fn period_mean(&self) -> f64 {
+        if self.leak_rate <= 0.0 {
+            return f64::NAN;
+        }
+        let settled = self.inner.period_mean() / self.leak_rate;
+        if settled.is_nan() {
+            return f64::NAN;
+        }
+        settled.max(self.get_minimum()).min(self.get_maximum())
+    }
+
+    fn calculate_noiseless(&self, time: f64) -> f64 {
+        let input = self.inner.calculate_noiseless(time);
+        self.step(&self.noiseless_state, time, input)
+    }
+
+    fn calculate_float(&self, time: f64) -> f64 {
+        let input = self.inner.calculate_float(time);
+        self.step(&self.state, time, input)
+    }
+}
+
+#[cfg(test)]
+mod wrapper_tests {
+    use super::*;
+    use crate::dft::power_spectrum;
+    use crate::signal_generator::{get_max_limit, get_min_limit};
+    use crate::signal_type::generators;
+
+    #[test]
+    fn dc_blocker_test() {
+        let constant = generators::test_constant(1000.0);
+        let blocked = constant.with_dc_blocker(1.0);
+
+        let values: Vec<i64> = (0..500)
+            .map(|i| blocked.calculate(i as f64 * 0.01))
+            .collect();
+        assert!(values[0] > 900);
+        // Only the sample-to-sample noise should make it through once the DC has decayed
+        assert!(values[499].abs() < 50);
+
+        let sine = generators::test_sine(1000.0, 1.0);
+        let blocked = sine.with_dc_blocker(0.01);
+
+        let peak = (0..500)
+            .map(|i| blocked.calculate(i as f64 * 0.01))
+            .skip(400)
+            .map(|value| value.abs())
+            .max()
+            .unwrap();
+        assert!(peak > 900);
+    }
+
+    #[test]
+    fn band_limited_test() {
+        // One second oversampled 16 times, to see what lies above the 50 Hz Nyquist frequency
+        let sample_rate = 100.0;
+        let times: Vec<f64> = (0..1600).map(|i| i as f64 / 1600.0).collect();
+        let spectrum_of = |signal: &dyn Signal| {
+            let samples: Vec<f64> = times
+                .iter()
+                .map(|&t| signal.calculate_noiseless(t))
+                .collect();
+            power_spectrum(&samples)
+        };
+
+        for signal_type in [SignalType::Square, SignalType::Sawtooth] {
+            let naive = crate::signal_generator::SignalGenerator::new(
+                signal_type,
+                get_min_limit(),
+                get_max_limit(),
+                1000.0,
+                1.0 / 7.0,
+                0.0,
+                16,
+                true,
+                1.0,
+                0.0,
+            )
+            .unwrap()
+            .inner;
+
+            let naive_spectrum = spectrum_of(naive.as_ref());
+            let total = naive_spectrum.iter().sum::<f64>();
+            assert!(naive_spectrum.iter().skip(51).sum::<f64>() > total * 1e-3);
+
+            let band_limited = BandLimited::new(naive, sample_rate);
+            let band_limited_spectrum = spectrum_of(&band_limited);
+            assert!(band_limited_spectrum.iter().skip(51).sum::<f64>() < total * 1e-9);
+        }
+
+        // Other generators aren't the waveform they report, so are left alone
+        let impulses = generators::ImpulseTrain {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            period: 1.0 / 7.0,
+            phase: 0.0,
+            sample_step: 1.0 / 1600.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        let expected: Vec<f64> = times
+            .iter()
+            .map(|&t| impulses.calculate_noiseless(t))
+            .collect();
+        let band_limited = BandLimited::new(Box::new(impulses), sample_rate);
+        for (&t, &value) in times.iter().zip(&expected) {
+            assert_eq!(band_limited.calculate_noiseless(t), value);
+        }
+
+        // Without a cycle the square is held as it is, and a huge number of harmonics is cut short
+        let square = |period: f64| generators::Square {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            period,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        for period in [f64::INFINITY, f64::NAN, 0.0, -1.0] {
+            let band_limited = BandLimited::new(Box::new(square(period)), sample_rate);
+            assert_eq!(
+                band_limited.calculate_noiseless(0.1),
+                square(period).calculate_noiseless(0.1)
+            );
+        }
+        let band_limited = BandLimited::new(Box::new(square(1e6)), 1e12);
+        assert!((band_limited.calculate_noiseless(2.5e5) - 1000.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn soft_start_test() {
+        let constant = generators::test_constant(1000.0);
+        let soft = SoftStart::new(Box::new(constant), 2.0);
+
+        assert_eq!(soft.calculate(0.0), 0);
+        assert!((soft.calculate(1.0) - 500).abs() <= 10);
+        for i in 20..100 {
+            assert!((soft.calculate(i as f64 * 0.1) - 1000).abs() <= 10);
+        }
+
+        // The envelope only ever rises
+        let envelope: Vec<f64> = (0..=20).map(|i| soft.envelope(i as f64 * 0.1)).collect();
+        assert!(envelope.windows(2).all(|pair| pair[1] >= pair[0]));
+    }
+
+    #[test]
+    fn saturation_recovery_test() {
+        let sine = || generators::Sine {
+            minimum: -800.0,
+            maximum: 800.0,
+            ..generators::test_sine(1000.0, 1.0)
+        };
+        let reference = sine();
+        let recovering = SaturationRecovery::new(Box::new(sine()), 0.1);
+
+        // The sine clips from about 0.148s to 0.352s, so recovers until about 0.452s
+        for i in 0..700 {
+            let time = i as f64 * 0.001;
+            let value = recovering.calculate_float(time);
+            let expected = reference.calculate_float(time);
+
+            if (0.36..0.42).contains(&time) {
+                assert!(value < expected - 20.0, "{time}: {value} {expected}");
+            } else if time > 0.47 {
+                assert!(
+                    (value - expected).abs() < 25.0,
+                    "{time}: {value} {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn waveform_bounds_test() {
+        let sine = |rail: f64| generators::Sine {
+            minimum: -rail,
+            maximum: rail,
+            ..generators::test_sine(1000.0, 1.0)
+        };
+        let peak = |signal: &dyn Signal| (0..100).map(|i| signal.calculate(i as f64 * 0.01)).max();
+
+        // Waveform bounds tighter than the rails
+        let bounded = WaveformBounds::new(Box::new(sine(800.0)), -500.0, 500.0);
+        assert_eq!(peak(&bounded), Some(500));
+
+        // Rails tighter than the waveform bounds
+        let railed = WaveformBounds::new(Box::new(sine(300.0)), -500.0, 500.0);
+        assert_eq!(peak(&railed), Some(300));
+    }
+
+    #[test]
+    fn time_quantized_test() {
+        let sawtooth = generators::Sawtooth {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            period: 1.0,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        let held = TimeQuantized::new(Box::new(sawtooth), 0.25);
+
+        // Constant within each interval
+        for step in 0..4 {
+            let start = step as f64 * 0.25;
+            let value = held.calculate_clean(start);
+            for i in 1..25 {
+                assert_eq!(held.calculate_clean(start + i as f64 * 0.01), value);
+            }
+        }
+
+        // Stepping at the boundaries
+        assert_eq!(held.calculate_clean(0.2499), -1000);
+        assert_eq!(held.calculate_clean(0.25), -500);
+        assert_eq!(held.calculate_clean(0.5), 0);
+        assert_eq!(held.calculate_clean(0.75), 500);
+    }
+
+    #[test]
+    fn dc_blocker_group_delay_test() {
+        let sine = |period: f64| generators::test_sine(1000.0, period);
+        let rc = 1.0 / (2.0 * PI);
+
+        // At the cutoff the delay is half the time constant, and well above it there is almost none
+        let at_cutoff = DcBlocker::new(Box::new(sine(1.0)), 1.0);
+        assert!((at_cutoff.group_delay() - rc / 2.0).abs() < 1e-12);
+        let above = DcBlocker::new(Box::new(sine(0.01)), 1.0);
+        assert!(above.group_delay() < rc / 1000.0);
+
+        // A callback has no fundamental to be delayed at
+        let callback = generators::Callback::new(
+            |time| time,
+            get_min_limit(),
+            get_max_limit(),
+            16,
+            true,
+            1.0,
+            0.0,
+        );
+        assert!(DcBlocker::new(Box::new(callback), 1.0)
+            .group_delay()
+            .is_nan());
+    }
+
+    #[test]
+    fn mains_hum_test() {
+        let sine = || generators::test_sine(1000.0, 0.2);
+        let hummed = sine().with_mains_hum(60.0, 0.05);
+        let clean = sine();
+
+        // One second at 1 kHz, so each bin is 1 Hz
+        let sample = |signal: &dyn Signal| {
+            let samples: Vec<f64> = (0..1000)
+                .map(|i| signal.calculate_noiseless(i as f64 / 1000.0))
+                .collect();
+            power_spectrum(&samples)
+        };
+        let hummed = sample(&hummed);
+        let clean = sample(&clean);
+
+        assert!(hummed[60] > hummed[5] / 1000.0);
+        assert!(clean[60] < clean[5] / 1e12);
+        assert!((hummed[5] - clean[5]).abs() < clean[5] * 1e-6);
+    }
+
+    #[test]
+    fn temp_drift_test() {
+        let constant = generators::test_constant(1000.0);
+        // Warming up by a degree a second from 25 degrees
+        let temperature = generators::Callback::new(
+            |time| 25.0 + time,
+            get_min_limit(),
+            get_max_limit(),
+            16,
+            true,
+            1.0,
+            0.0,
+        );
+        let drifting = TempDrift::new(Box::new(constant), Box::new(temperature), 0.01, 25.0);
+
+        assert_eq!(drifting.calculate_clean(0.0), 1000);
+        assert_eq!(drifting.calculate_clean(10.0), 1100);
+        let outputs: Vec<i64> = (0..20)
+            .map(|i| drifting.calculate_clean(i as f64))
+            .collect();
+        assert!(outputs.windows(2).all(|pair| pair[1] > pair[0]));
+    }
+
+    #[test]
+    fn difference_test() {
+        let sine = |amplitude: f64, phase: f64| generators::Sine {
+            phase,
+            ..generators::test_sine(amplitude, 1.0)
+        };
+        let template = sine(2000.0, 0.0);
+
+        let same = difference(
+            Box::new(sine(1000.0, 0.0)),
+            Box::new(sine(1000.0, 0.0)),
+            &template,
+        );
+        let inverse = difference(
+            Box::new(sine(1000.0, 0.0)),
+            Box::new(sine(1000.0, 0.5)),
+            &template,
+        );
+        let doubled = sine(2000.0, 0.0);
+
+        for i in 0..100 {
+            let time = i as f64 * 0.01;
+            assert_eq!(same.calculate_clean(time), 0);
+            assert!((inverse.calculate_clean(time) - doubled.calculate_clean(time)).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn period_mean_test() {
+        let constant = |amplitude| generators::test_constant(amplitude);
+
+        // Forwarded through wrappers that leave the mean alone
+        let hummed = constant(1000.0).with_mains_hum(60.0, 0.1);
+        assert_eq!(hummed.period_mean(), 1000.0);
+        assert_eq!(SoftStart::new(Box::new(hummed), 1.0).period_mean(), 1000.0);
+
+        // Removed by the DC blocker, so its AC component is what gets through
+        let blocked = constant(1000.0).with_dc_blocker(1.0);
+        assert_eq!(blocked.period_mean(), 0.0);
+        let ac: Vec<f64> = (0..500)
+            .map(|i| blocked.ac_component(i as f64 * 0.01))
+            .collect();
+        assert!(ac[0] > 900.0);
+        assert!(ac[499].abs() < 50.0, "{}", ac[499]);
+
+        let dropped = Dropouts::new(Box::new(constant(1000.0)), 0.25, 0.01, 228);
+        assert_eq!(dropped.period_mean(), 750.0);
+        let leaky = LeakyIntegrator::new(Box::new(constant(10.0)), 0.1);
+        assert!((leaky.period_mean() - 100.0).abs() < 1e-9);
+        assert!(LeakyIntegrator::new(Box::new(constant(10.0)), 0.0)
+            .period_mean()
+            .is_nan());
+
+        // Otherwise the running mean is taken out instead
+        let mapped = Transfer::new(
+            Box::new(constant(1000.0)),
+            vec![(0.0, 0.0), (2000.0, 500.0)],
+        );
+        assert!(mapped.period_mean().is_nan());
+        for i in 1..100 {
+            assert!(mapped.ac_component(i as f64 * 0.1).abs() <= 10.0);
+        }
+        let gated = Gated::new(Box::new(constant(1000.0)), Box::new(constant(1.0)), 0.0);
+        assert!(gated.period_mean().is_nan());
+        assert!(gated.ac_component(1.0).abs() <= 20.0);
+    }
+
+    #[test]
+    fn effective_peak_test() {
+        let sine = || generators::test_sine(1000.0, 1.0);
+
+        // Adding hum at 0.2 of the amplitude peaks at amplitude * (1 + 0.2), as the sine and the
+        // hum can crest together
+        let hummed = sine().with_mains_hum(60.0, 0.2);
+        assert_eq!(hummed.effective_peak(), 1200.0);
+
+        // The peak propagates through any number of wrappers
+        let softened = SoftStart::new(Box::new(hummed), 1.0);
+        let bounded = WaveformBounds::new(Box::new(softened), -1100.0, 1100.0);
+        assert_eq!(bounded.effective_peak(), 1100.0);
+
+        let both = difference(Box::new(sine()), Box::new(bounded), &sine());
+        assert_eq!(both.effective_peak(), 2100.0);
+    }
+
+    #[test]
+    fn wrapped_generator_test() {
+        // A generator's own descriptions come through a wrapper rather than the defaults
+        let ramp = generators::Ramp {
+            minimum: -100.0,
+            maximum: 300.0,
+            ramp_duration: 2.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        let soft_started = SoftStart::new(Box::new(ramp), 1.0);
+        assert_eq!(soft_started.time_to_next_period(0.5), f64::INFINITY);
+        assert_eq!(soft_started.max_slew_rate(), 200.0);
+
+        let impulses = || generators::ImpulseTrain {
+            minimum: 0.0,
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            period: 1.0,
+            phase: 0.0,
+            sample_step: 0.01,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        let quantized = TimeQuantized::new(Box::new(impulses()), 0.01);
+        assert_eq!(quantized.recommended_bits(0.0), 10);
+        assert_eq!(
+            quantized.recommended_bits(0.0),
+            impulses().recommended_bits(0.0)
+        );
+        assert_eq!(quantized.max_slew_rate(), 0.0);
+
+        let hop = generators::FrequencyHop {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            frequencies: vec![5.0, 10.0],
+            hop_period: 0.1,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        let delayed = ConversionDelay::new(Box::new(hop), 0.001);
+        assert_eq!(delayed.time_to_next_period(0.05), f64::INFINITY);
+        assert!(delayed.period_jitter_std(0.0, 1.2, 0.001).is_nan());
+    }
+
+    #[test]
+    fn seeded_test() {
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let seeded = |seed: u64| Seeded::new(Box::new(generators::test_constant(1000.0)), seed);
+        let trace = |signal: &Seeded| -> Vec<f64> {
+            (0..100)
+                .map(|i| signal.calculate_float(i as f64 * 0.01))
+                .collect()
+        };
+
+        let first = trace(&seeded(265));
+        assert_eq!(first, trace(&seeded(265)));
+        assert_ne!(first, trace(&seeded(266)));
+        // Still noisy, just repeatably so
+        assert!(first.iter().any(|&value| value != 1000.0));
+    }
+
+    #[test]
+    fn conversion_delay_test() {
+        let ramp = || {
+            generators::Callback::new(
+                |time| time * 1000.0,
+                get_min_limit(),
+                get_max_limit(),
+                16,
+                true,
+                1.0,
+                0.0,
+            )
+        };
+        let first = ConversionDelay::new(Box::new(ramp()), 0.001);
+        let second = ConversionDelay::new(Box::new(ramp()), 0.003);
+
+        let table = generators::channel_table(&[&first, &second], &[0.0, 1.0, 2.0]);
+        assert_eq!(table, vec![vec![1, 3], vec![1001, 1003], vec![2001, 2003]]);
+    }
+
+    #[test]
+    fn period_jitter_std_test() {
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let sine = || generators::test_sine(1000.0, 1.0);
+        let jitter_std = |jitter: f64| {
+            let jittered = PhaseNoise::new(Box::new(sine()), jitter);
+            generators::with_seed(259, || jittered.period_jitter_std(0.0, 200.0, 0.02))
+        };
+
+        let clean = jitter_std(0.0);
+        let low = jitter_std(0.002);
+        let high = jitter_std(0.005);
+
+        // The amplitude noise alone moves the crossings by well under a millisecond
+        assert!(clean < 0.002, "{clean}");
+        assert!(low > clean, "{low} vs {clean}");
+        assert!(high > low, "{high} vs {low}");
+    }
+
+    #[test]
+    fn gated_test() {
+        let constant = generators::test_constant(1000.0);
+        // On for the first half of every 2 seconds
+        let relay = generators::Square {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1.0,
+            period: 2.0,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+        let gated = Gated::new(Box::new(constant), Box::new(relay), 0.0);
+
+        for i in 0..100 {
+            let time = i as f64 * 0.1 + 0.05;
+            let value = gated.calculate(time);
+            if time % 2.0 < 1.0 {
+                assert!((value - 1000).abs() <= 10, "{value} at {time}");
+            } else {
+                assert_eq!(value, 0, "at {time}");
+            }
+        }
+    }
+
+    #[test]
+    fn step_response_test() {
+        let square = || generators::Square {
+            minimum: get_min_limit(),
+            maximum: get_max_limit(),
+            amplitude: 1000.0,
+            period: 1.0,
+            phase: 0.0,
+            num_bits: 16,
+            is_signed: true,
+            scale: 1.0,
+            offset: 0.0,
+        };
+
+        // An ideal edge rises within a single sample
+        assert!(square().rise_time(10.0, 90.0) <= 0.001);
+        assert!(square().overshoot().abs() < 1e-12);
+
+        // A full 2000 unit swing at 20000 units/s takes 0.1 s, so 10% to 90% takes 0.08 s
+        let slewed = SlewLimited::new(Box::new(square()), 20000.0);
+        assert!((slewed.rise_time(10.0, 90.0) - 0.08).abs() < 0.002);
+        assert!(slewed.overshoot().abs() < 1e-12);
+
+        // Band limiting rings past the edge, the Gibbs phenomenon
+        let filtered = BandLimited::new(Box::new(square()), 100.0);
+        assert!(filtered.rise_time(10.0, 90.0) > 0.001);
+        assert!(
+            (filtered.overshoot() - 0.09).abs() < 0.01,
+            "{}",
+            filtered.overshoot()
+        );
+    }
+
+    #[test]
+    fn transfer_test() {
+        // The input is the time, so each time reads one point of the curve
+        let ramp = generators::Callback::new(|time| time, -1000.0, 1000.0, 16, true, 1.0, 0.0);
+        let curve = vec![(0.0, 100.0), (1.0, 50.0), (3.0, 30.0)];
+        let transfer = Transfer::new(Box::new(ramp), curve);
+
+        // At the breakpoints
+        assert_eq!(transfer.calculate(0.0), 100);
+        assert_eq!(transfer.calculate(1.0), 50);
+        assert_eq!(transfer.calculate(3.0), 30);
+
+        // Between the breakpoints
+        assert_eq!(transfer.calculate(0.5), 75);
+        assert_eq!(transfer.calculate(2.5), 35);
+
+        // Beyond the ends
+        assert_eq!(transfer.calculate(-1.0), 100);
+        assert_eq!(transfer.calculate(5.0), 30);
+        assert_eq!(transfer.effective_peak(), 100.0);
+    }
+
+    #[test]
+    fn adc_error_test() {
+        let constant = |amplitude: f64| generators::Constant {
+            num_bits: 12,
+            scale: 0.5,
+            ..generators::test_constant(amplitude)
+        };
+        let count = |signal: &dyn Signal| signal.calculate_pair(0.0).1;
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let deterministic = generators::Deterministic::enable();
+        let ideal: Vec<_> = [0.0, 100.0, -250.0]
+            .into_iter()
+            .map(|amplitude| {
+                let ideal = AdcError::new(Box::new(constant(amplitude)), 0.0, 0.0);
+                let expected = constant(amplitude);
+                (
+                    (count(&ideal), ideal.calculate(0.0)),
+                    (count(&expected), expected.calculate(0.0)),
+                )
+            })
+            .collect();
+        let miscalibrated = AdcError::new(Box::new(constant(100.0)), 0.02, 3.0);
+        let miscalibrated = (count(&miscalibrated), miscalibrated.calculate(0.0));
+        let saturated = count(&AdcError::new(Box::new(constant(1000.0)), 0.5, 0.0));
+        drop(deterministic);
+
+        // No errors is the ideal mapping
+        for (actual, expected) in ideal {
+            assert_eq!(actual, expected);
+        }
+
+        // 100 reads as 200 counts ideally, then 2% high and 3 counts up
+        assert_eq!(miscalibrated, (207, 104));
+
+        // The errors can't push the count past the bits
+        assert_eq!(saturated, 2047);
+    }
+
+    #[test]
+    fn leaky_integrator_test() {
+        use generators::sample_times;
+
+        let constant = |value: f64, maximum: f64| {
+            generators::Callback::new(move |_| value, -1000.0, maximum, 16, true, 0.01, 0.0)
+        };
+        let settle = |integrator: &LeakyIntegrator| {
+            sample_times(0.0, 10.0, 0.001).fold(0.0, |_, time| integrator.calculate_float(time))
+        };
+
+        // 5 in, leaking at 2 per second, settles at 5 / 2
+        let integrator = LeakyIntegrator::new(Box::new(constant(5.0, 1000.0)), 2.0);
+        assert!((settle(&integrator) - 2.5).abs() < 1e-6);
+
+        // Doubling the input doubles the level, doubling the leak halves it
+        let integrator = LeakyIntegrator::new(Box::new(constant(10.0, 1000.0)), 2.0);
+        assert!((settle(&integrator) - 5.0).abs() < 1e-6);
+        let integrator = LeakyIntegrator::new(Box::new(constant(5.0, 1000.0)), 4.0);
+        assert!((settle(&integrator) - 1.25).abs() < 1e-6);
+
+        // The bucket saturates at the maximum instead of reaching 5 / 0.1
+        let integrator = LeakyIntegrator::new(Box::new(constant(5.0, 20.0)), 0.1);
+        assert_eq!(settle(&integrator), 20.0);
+    }
+
+    #[test]
+    fn burst_dropouts_test() {
+        // A signal that is really 0 half the time, so the dropouts can't be spotted by value
+        let square = generators::Callback::new(
+            |time| {
+                if (time * 10.0).fract() < 0.5 {
+                    0.0
+                } else {
+                    100.0
+                }
+            },
+            -1000.0,
+            1000.0,
+            16,
+            true,
+            1.0,
+            0.0,
+        );
+        let bursty = BurstDropouts::new(Box::new(square), 0.02, 0.25, 288);
+
+        // The length of every run of dropped samples
+        let mut bursts = Vec::new();
+        let mut run = 0;
+        for i in 0..200_000 {
+            let value = bursty.calculate(i as f64 * 0.001);
+            if bursty.is_dropped() {
+                assert_eq!(value, 0);
+                run += 1;
+            } else if run > 0 {
+                bursts.push(run);
+                run = 0;
+            }
+        }
+
+        let mean = bursts.iter().sum::<usize>() as f64 / bursts.len() as f64;
+        assert!((mean - 4.0).abs() < 0.2, "{mean}");
+        assert!(bursts.iter().any(|&length| length > 1));
+
+        // Bad for 1 / 0.25 samples out of every 1 / 0.02 + 1 / 0.25, about 7%
+        let dropped = bursts.iter().sum::<usize>() as f64 / 200_000.0;
+        assert!((dropped - 4.0 / 54.0).abs() < 0.01, "{dropped}");
+    }
+
+    #[test]
+    fn dithered_test() {
+        // Callbacks have no noise, so a slow ramp only picks up the quantization error
+        let ramp =
+            || generators::Callback::new(|time| 0.37 * time, -1000.0, 1000.0, 16, true, 1.0, 0.0);
+        let times: Vec<f64> = generators::sample_times(0.0, 1000.0, 0.01).collect();
+
+        // The quantization error and its correlation with the next error
+        let errors = |signal: &dyn Signal| {
+            let errors: Vec<f64> = times
+                .iter()
+                .map(|&time| signal.calculate(time) as f64 - 0.37 * time)
+                .collect();
+            let power = errors.iter().map(|e| e * e).sum::<f64>();
+            let lagged = errors.windows(2).map(|pair| pair[0] * pair[1]).sum::<f64>();
+            (errors, lagged / power)
+        };
+
+        // The error power at inputs just off a count and halfway between counts
+        let modulation = |errors: &[f64]| {
+            let power_where = |near: fn(f64) -> bool| {
+                let (sum, count) = times
+                    .iter()
+                    .zip(errors)
+                    .filter(|(&time, _)| near((0.37 * time).fract()))
+                    .fold((0.0, 0), |(sum, count), (_, e)| (sum + e * e, count + 1));
+                sum / count as f64
+            };
+            (
+                power_where(|fraction| !(0.05..=0.95).contains(&fraction)),
+                power_where(|fraction| (0.45..=0.55).contains(&fraction)),
+            )
+        };
+
+        let _lock = generators::NOISE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        // Without dither the error follows the ramp, so each error is almost the next
+        let (_, plain) = errors(&ramp());
+        assert!(plain > 0.9, "{plain}");
+
+        // Triangular dither leaves the error white, with the same power everywhere
+        let triangular = Dithered::new(Box::new(ramp()), DitherKind::Triangular);
+        let (tpdf, correlation) = errors(&triangular);
+        assert!(correlation.abs() < 0.05, "{correlation}");
+        let (on_count, between) = modulation(&tpdf);
+        assert!((on_count - between).abs() < 0.05, "{on_count} {between}");
+        assert!((on_count - 0.25).abs() < 0.05, "{on_count}");
+
+        // Rectangular dither's error power still depends on where the input is
+        let rectangular = Dithered::new(Box::new(ramp()), DitherKind::Rectangular);
+        let (rpdf, _) = errors(&rectangular);
+        let (on_count, between) = modulation(&rpdf);
+        assert!(between > on_count + 0.15, "{on_count} {between}");
+
+        let (_, deterministic) = {
+            let _deterministic = generators::Deterministic::enable();
+            errors(&triangular)
+        };
+        assert_eq!(deterministic, plain);
+    }
+}
@@ -0,0 +1,55 @@
+/// The non-overlapping Allan deviation of a buffer of raw counts taken at `sample_rate` Hz, for
+/// each averaging time in `taus` (in seconds)
+///
+/// Each tau is rounded to a whole number of samples. The buffer is split into consecutive
+/// averages of that many samples, and the deviation is `sqrt(mean((next - this)²) / 2)` over
+/// neighbouring averages. NaN for a tau shorter than a sample or too long for two averages.
+pub fn allan_deviation(samples: &[i64], sample_rate: f64, taus: &[f64]) -> Vec<f64> {
+    taus.iter()
+        .map(|&tau| {
+            let size = (tau * sample_rate).round();
+            if size.is_nan() || size < 1.0 || size * 2.0 > samples.len() as f64 {
+                return f64::NAN;
+            }
+
+            let averages: Vec<f64> = samples
+                .chunks_exact(size as usize)
+                .map(|chunk| chunk.iter().map(|&count| count as f64).sum::<f64>() / size)
+                .collect();
+            let sum: f64 = averages
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).powi(2))
+                .sum();
+            (sum / (2.0 * (averages.len() - 1) as f64)).sqrt()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod stability_tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn allan_deviation_test() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples: Vec<i64> = (0..100_000).map(|_| rng.gen_range(-1000..=1000)).collect();
+
+        // White noise averages down as tau^(-1/2), a slope of -1/2 on a log-log plot
+        let taus = [0.001, 0.01, 0.1];
+        let deviations = allan_deviation(&samples, 1000.0, &taus);
+        for (pair, tau) in deviations.windows(2).zip(taus.windows(2)) {
+            let slope = (pair[1] / pair[0]).log10() / (tau[1] / tau[0]).log10();
+            assert!((slope + 0.5).abs() < 0.05, "{deviations:?}");
+        }
+
+        // Uniform noise over ±1000 has a standard deviation of 1000/√3 at a single sample
+        assert!(
+            (deviations[0] - 1000.0 / 3f64.sqrt()).abs() < 10.0,
+            "{deviations:?}"
+        );
+
+        let out_of_range = allan_deviation(&samples, 1000.0, &[0.0001, 60.0]);
+        assert!(out_of_range.iter().all(|deviation| deviation.is_nan()));
+    }
+}